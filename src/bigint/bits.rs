@@ -0,0 +1,190 @@
+use bigint::BigInt;
+use bigint::digit::Limb;
+use bigint::limbs::LimbVec;
+use bigint::ops::add::sadd_digit;
+use bigint::ops::bit::TwosComplement;
+use bigint::ops::scalar::to_u128;
+use bigint::ops::sub::dsub;
+use bigint::sign::Sign;
+
+use std::ops::Range;
+
+/// Bit level queries and updates. Bit indices count from the least significant bit. Methods that
+/// look at individual bits of a negative number use the same infinite two's complement view as
+/// the bitwise operators.
+impl<L: Limb> BigInt<L> {
+    /// Returns the number of bits needed to represent the magnitude of self, or 0 if self is zero.
+    pub fn bits(&self) -> usize {
+        match self.digits.last() {
+            Some(&top) => self.digits.len() * L::BITS - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Returns bit `n` of the two's complement representation of self.
+    pub fn bit(&self, n: usize) -> bool {
+        let (digit, bit) = (n / L::BITS, n % L::BITS);
+        match self.digits.get(digit) {
+            None => self.is_negative(),
+            Some(&d) => {
+                let d = if self.is_negative() {
+                    let carry = self.digits[..digit].iter().all(|&x| x == L::ZERO);
+                    if carry { (!d).wrapping_add(L::ONE) } else { !d }
+                } else {
+                    d
+                };
+                (d >> bit) & L::ONE == L::ONE
+            }
+        }
+    }
+
+    /// Sets bit `n` of the two's complement representation of self to `value`.
+    pub fn set_bit(&mut self, n: usize, value: bool) {
+        if self.bit(n) == value {
+            return;
+        }
+        let (digit, bit) = (n / L::BITS, n % L::BITS);
+
+        if !self.is_negative() {
+            self.grow_to_hold(digit + 1);
+            self.digits[digit] ^= L::ONE << bit;
+            self.sign = Sign::Positive;
+        } else if value {
+            // Setting a clear bit adds 2^n, which shrinks the magnitude of a negative number.
+            // The bit is clear, so it lies below the top digit and the result stays negative.
+            dsub(&mut self.digits[digit..], L::ONE << bit);
+        } else {
+            self.grow_to_hold(digit + 1);
+            let carry = sadd_digit(&mut self.digits[digit..], L::ONE << bit);
+            if carry != L::ZERO {
+                self.digits.push(carry);
+            }
+        }
+        self.trim();
+    }
+
+    /// Returns the number of one bits in the magnitude of self.
+    pub fn count_ones(&self) -> usize {
+        self.digits.iter().map(|d| d.count_ones() as usize).sum()
+    }
+
+    /// Returns the number of trailing zero bits of self, or `None` if self is zero. This is the
+    /// same for a number and its negation.
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        self.digits
+            .iter()
+            .position(|&d| d != L::ZERO)
+            .map(|i| i * L::BITS + self.digits[i].trailing_zeros() as usize)
+    }
+
+    /// Returns true if self is a positive power of two.
+    pub fn is_power_of_two(&self) -> bool {
+        match self.digits.split_last() {
+            Some((top, rest)) => {
+                self.is_positive() && top.is_power_of_two() && rest.iter().all(|&d| d == L::ZERO)
+            }
+            None => false,
+        }
+    }
+
+    /// Returns bits `range.start` up to but not including `range.end` of the two's complement
+    /// representation of self, as a nonnegative number.
+    pub fn extract_bits(&self, range: Range<usize>) -> Self {
+        assert!(range.start <= range.end, "Invalid bit range");
+        let width = range.end - range.start;
+        let skip = range.start / L::BITS;
+
+        if width == 0 || (skip >= self.digits.len() && !self.is_negative()) {
+            return BigInt::default();
+        }
+
+        let mut out = if skip >= self.digits.len() {
+            // Entirely within the sign extension of a negative number.
+            BigInt::from_vec(LimbVec::from(vec![L::MAX; width.div_ceil(L::BITS)]))
+        } else {
+            let mut take = range.end.div_ceil(L::BITS) - skip;
+            if !self.is_negative() {
+                // Past the top limb a nonnegative number only has zero bits.
+                take = take.min(self.digits.len() - skip);
+            }
+            let digits = TwosComplement::new(self).skip(skip).take(take).collect();
+            BigInt::from_vec(digits) >> (range.start % L::BITS)
+        };
+
+        out.digits.truncate(width.div_ceil(L::BITS));
+        let top_bits = width % L::BITS;
+        if top_bits != 0 {
+            if let Some(top) = out.digits.get_mut(width / L::BITS) {
+                *top &= L::MAX >> (L::BITS - top_bits);
+            }
+        }
+        out.trimmed()
+    }
+
+    /// Returns the same bits as `extract_bits`, packed into a u64. Panics if the range is wider
+    /// than 64 bits.
+    pub fn extract_bits_u64(&self, range: Range<usize>) -> u64 {
+        assert!(range.end - range.start <= 64, "Bit range is wider than 64 bits");
+        to_u128(&self.extract_bits(range).digits) as u64
+    }
+}
+
+
+#[test]
+fn bit_query_test() {
+    let values = [-(1i64 << 40) - 3, -(1 << 32), -65, -2, -1, 0, 1, 7, 64, 1 << 32, 1 << 40 | 5];
+    for &x in values.iter() {
+        let big = BigInt::from(x);
+        for n in 0..63 {
+            assert_eq!(big.bit(n), (x >> n) & 1 == 1);
+        }
+        assert_eq!(big.bits(), 64 - x.abs().leading_zeros() as usize);
+        assert_eq!(big.count_ones(), x.abs().count_ones() as usize);
+        assert_eq!(big.is_power_of_two(), x > 0 && (x & (x - 1)) == 0);
+        if x == 0 {
+            assert_eq!(big.trailing_zeros(), None);
+        } else {
+            assert_eq!(big.trailing_zeros(), Some(x.trailing_zeros() as usize));
+        }
+    }
+}
+
+#[test]
+fn set_bit_test() {
+    let values = [-(1i64 << 40) - 3, -(1 << 32), -65, -2, -1, 0, 1, 7, 64, 1 << 32];
+    for &x in values.iter() {
+        for n in 0..62 {
+            let mut set = BigInt::from(x);
+            set.set_bit(n, true);
+            assert_eq!(set, BigInt::from(x | (1 << n)));
+
+            let mut cleared = BigInt::from(x);
+            cleared.set_bit(n, false);
+            assert_eq!(cleared, BigInt::from(x & !(1 << n)));
+        }
+    }
+}
+
+#[test]
+fn extract_bits_test() {
+    let values = [-(1i64 << 40) - 3, -(1 << 32), -65, -1, 0, 7, 1 << 32, 1 << 40 | 5];
+    for &x in values.iter() {
+        for &(lo, hi) in [(0, 0), (0, 5), (3, 40), (31, 33), (32, 64), (40, 63), (60, 200)].iter() {
+            let width = hi - lo;
+            let expected = if width >= 64 { x >> lo } else { (x >> lo) & ((1 << width) - 1) };
+            if width <= 64 && hi <= 64 {
+                assert_eq!(BigInt::from(x).extract_bits_u64(lo..hi), expected as u64);
+            }
+            if hi <= 64 {
+                assert_eq!(BigInt::from(x).extract_bits(lo..hi), BigInt::from(expected));
+            }
+        }
+    }
+    let ones = BigInt::from(-1).extract_bits(100..300);
+    assert_eq!(ones, (BigInt::one() << 200) - BigInt::one());
+
+    // Ranges far past the top of a nonnegative number must not allocate for their full width.
+    assert_eq!(BigInt::from(5u8).extract_bits(0..::std::usize::MAX), BigInt::from(5u8));
+    assert_eq!(BigInt::from(5u8).extract_bits(2..1 << 31), BigInt::one());
+    assert_eq!(BigInt::from(5u8).extract_bits(64..::std::usize::MAX), BigInt::zero());
+}
@@ -0,0 +1,253 @@
+use bigint::{BigDigit, BigInt, Limb, Sign};
+
+/// The order of the parts of a multi-part value, used both for the words of an exported number
+/// and for the bytes within each word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Order {
+    /// The most significant part comes first, as in big endian.
+    MostSignificantFirst,
+    /// The least significant part comes first, as in little endian.
+    LeastSignificantFirst,
+}
+
+/// Conversions to bytes. None of them depend on the limb type.
+impl<L: Limb> BigInt<L> {
+    /// Returns the sign of self and the bytes of its magnitude, most significant first. Zero is
+    /// a single zero byte.
+    pub fn to_bytes_be(&self) -> (Sign, Vec<u8>) {
+        let (sign, mut bytes) = self.to_bytes_le();
+        bytes.reverse();
+        (sign, bytes)
+    }
+
+    /// Returns the sign of self and the bytes of its magnitude, least significant first. Zero
+    /// is a single zero byte.
+    pub fn to_bytes_le(&self) -> (Sign, Vec<u8>) {
+        let mut bytes = magnitude_le(self);
+        if bytes.is_empty() {
+            bytes.push(0);
+        }
+        (self.sign, bytes)
+    }
+
+    /// Returns the shortest two's complement representation of self, most significant byte
+    /// first.
+    pub fn to_signed_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_signed_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Returns the shortest two's complement representation of self, least significant byte
+    /// first.
+    pub fn to_signed_bytes_le(&self) -> Vec<u8> {
+        let mut bytes = magnitude_le(self);
+        let sign_byte = if self.is_negative() {
+            negate_bytes(&mut bytes);
+            0xff
+        } else {
+            0
+        };
+
+        // Add a byte if the top bit does not already match the sign.
+        match bytes.last() {
+            Some(&top) if (top ^ sign_byte) & 0x80 == 0 => {}
+            _ => bytes.push(sign_byte),
+        }
+        bytes
+    }
+
+    /// Writes the magnitude of self as a sequence of `word_size` byte words, in the manner of
+    /// GMP's `mpz_export`. `word_order` gives the order of the words and `byte_order` the order
+    /// of the bytes within each word. The top word is zero padded, and zero exports as no words
+    /// at all. Panics if `word_size` is zero.
+    pub fn export_words(&self, word_size: usize, word_order: Order, byte_order: Order) -> Vec<u8> {
+        assert!(word_size != 0, "Word size must be nonzero");
+        let mut bytes = magnitude_le(self);
+        let words = bytes.len().div_ceil(word_size);
+        bytes.resize(words * word_size, 0);
+
+        if byte_order == Order::MostSignificantFirst {
+            for word in bytes.chunks_mut(word_size) {
+                word.reverse();
+            }
+        }
+        if word_order == Order::MostSignificantFirst {
+            reverse_words(&mut bytes, word_size);
+        }
+        bytes
+    }
+}
+
+/// Conversions from bytes. These build BigInts with the default limb type.
+impl BigInt {
+    /// Creates a BigInt with the given sign and a magnitude read from bytes, most significant
+    /// first.
+    pub fn from_bytes_be(sign: Sign, bytes: &[u8]) -> BigInt {
+        let mut le = bytes.to_vec();
+        le.reverse();
+        BigInt::from_bytes_le(sign, &le)
+    }
+
+    /// Creates a BigInt with the given sign and a magnitude read from bytes, least significant
+    /// first.
+    pub fn from_bytes_le(sign: Sign, bytes: &[u8]) -> BigInt {
+        let digits = bytes
+            .chunks(<BigDigit as Limb>::BITS / 8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .rev()
+                    .fold(0, |acc: BigDigit, &b| ((acc as u128) << 8) as BigDigit | b as BigDigit)
+            })
+            .collect();
+        BigInt::from_sign_limbs(sign, digits)
+    }
+
+    /// Reads a two's complement number, most significant byte first. An empty slice is zero.
+    pub fn from_signed_bytes_be(bytes: &[u8]) -> BigInt {
+        let mut le = bytes.to_vec();
+        le.reverse();
+        BigInt::from_signed_bytes_le(&le)
+    }
+
+    /// Reads a two's complement number, least significant byte first. An empty slice is zero.
+    pub fn from_signed_bytes_le(bytes: &[u8]) -> BigInt {
+        match bytes.last() {
+            Some(&top) if top & 0x80 != 0 => {
+                let mut magnitude = bytes.to_vec();
+                negate_bytes(&mut magnitude);
+                BigInt::from_bytes_le(Sign::Negative, &magnitude)
+            }
+            _ => BigInt::from_bytes_le(Sign::Positive, bytes),
+        }
+    }
+
+    /// Reads a nonnegative BigInt from a sequence of `word_size` byte words laid out as by
+    /// `export_words`, in the manner of GMP's `mpz_import`. Panics if `word_size` is zero or does
+    /// not divide the length of `bytes`.
+    pub fn import_words(
+        bytes: &[u8],
+        word_size: usize,
+        word_order: Order,
+        byte_order: Order,
+    ) -> BigInt {
+        assert!(word_size != 0, "Word size must be nonzero");
+        assert_eq!(bytes.len() % word_size, 0, "Input is not a whole number of words");
+        let mut le = bytes.to_vec();
+
+        if word_order == Order::MostSignificantFirst {
+            reverse_words(&mut le, word_size);
+        }
+        if byte_order == Order::MostSignificantFirst {
+            for word in le.chunks_mut(word_size) {
+                word.reverse();
+            }
+        }
+        BigInt::from_bytes_le(Sign::Positive, &le)
+    }
+}
+
+/// Returns the bytes of the magnitude of `x`, least significant first, without trailing zeroes.
+fn magnitude_le<L: Limb>(x: &BigInt<L>) -> Vec<u8> {
+    let mut bytes: Vec<u8> = x
+        .digits
+        .iter()
+        .flat_map(|&d| (0..L::BITS / 8).map(move |i| (d.to_u128() >> (8 * i)) as u8))
+        .collect();
+    while let Some(&0) = bytes.last() {
+        bytes.pop();
+    }
+    bytes
+}
+
+/// Replaces little-endian `bytes` with their two's complement negation.
+fn negate_bytes(bytes: &mut [u8]) {
+    let mut carry = true;
+    for b in bytes.iter_mut() {
+        let (res, c) = (!*b).overflowing_add(carry as u8);
+        *b = res;
+        carry = c;
+    }
+}
+
+/// Reverses the order of the `word_size` byte words in `bytes`, keeping each word intact.
+fn reverse_words(bytes: &mut [u8], word_size: usize) {
+    bytes.reverse();
+    for word in bytes.chunks_mut(word_size) {
+        word.reverse();
+    }
+}
+
+
+#[test]
+fn bytes_test() {
+    use std::str::FromStr;
+    let x = BigInt::from_str("-1311768467463790320").unwrap();
+    assert_eq!(
+        x.to_bytes_be(),
+        (Sign::Negative, vec![0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0])
+    );
+    assert_eq!(
+        x.to_bytes_le(),
+        (Sign::Negative, vec![0xf0, 0xde, 0xbc, 0x9a, 0x78, 0x56, 0x34, 0x12])
+    );
+    assert_eq!(BigInt::from_bytes_be(Sign::Negative, &x.to_bytes_be().1), x);
+    assert_eq!(BigInt::from_bytes_le(Sign::Negative, &x.to_bytes_le().1), x);
+
+    assert_eq!(BigInt::zero().to_bytes_be(), (Sign::Zero, vec![0]));
+    assert_eq!(BigInt::from_bytes_be(Sign::Positive, &[0, 0, 1]), BigInt::one());
+    assert_eq!(BigInt::from_bytes_be(Sign::Zero, &[5]), BigInt::zero());
+    assert_eq!(BigInt::from_bytes_le(Sign::Positive, &[]), BigInt::zero());
+}
+
+#[test]
+fn signed_bytes_test() {
+    let cases: [(i64, &[u8]); 11] = [
+        (0, &[0]),
+        (1, &[1]),
+        (127, &[0x7f]),
+        (128, &[0, 0x80]),
+        (-1, &[0xff]),
+        (-128, &[0x80]),
+        (-129, &[0xff, 0x7f]),
+        (-256, &[0xff, 0]),
+        (65535, &[0, 0xff, 0xff]),
+        (-(1 << 40), &[0xff, 0, 0, 0, 0, 0]),
+        (::std::i64::MIN, &[0x80, 0, 0, 0, 0, 0, 0, 0]),
+    ];
+    for &(value, be) in cases.iter() {
+        let big = BigInt::from(value);
+        let mut le = be.to_vec();
+        le.reverse();
+        assert_eq!(big.to_signed_bytes_be(), be);
+        assert_eq!(big.to_signed_bytes_le(), le);
+        assert_eq!(BigInt::from_signed_bytes_be(be), big);
+        assert_eq!(BigInt::from_signed_bytes_le(&le), big);
+    }
+    assert_eq!(BigInt::from_signed_bytes_be(&[]), BigInt::zero());
+    assert_eq!(BigInt::from_signed_bytes_be(&[0xff, 0xff, 0xfe]), -BigInt::from(2u8));
+}
+
+#[test]
+fn export_words_test() {
+    use self::Order::*;
+    let x = BigInt::from(0x0102_0304_0506u64);
+
+    let export = |size, order, endian| x.export_words(size, order, endian);
+    assert_eq!(export(4, MostSignificantFirst, MostSignificantFirst), [0, 0, 1, 2, 3, 4, 5, 6]);
+    assert_eq!(export(4, MostSignificantFirst, LeastSignificantFirst), [2, 1, 0, 0, 6, 5, 4, 3]);
+    assert_eq!(export(4, LeastSignificantFirst, MostSignificantFirst), [3, 4, 5, 6, 0, 0, 1, 2]);
+    assert_eq!(export(4, LeastSignificantFirst, LeastSignificantFirst), [6, 5, 4, 3, 2, 1, 0, 0]);
+    assert_eq!(export(3, MostSignificantFirst, LeastSignificantFirst), [3, 2, 1, 6, 5, 4]);
+    assert!(BigInt::zero().export_words(8, MostSignificantFirst, MostSignificantFirst).is_empty());
+
+    for &order in [MostSignificantFirst, LeastSignificantFirst].iter() {
+        for &endian in [MostSignificantFirst, LeastSignificantFirst].iter() {
+            for size in 1..10 {
+                let words = (-x.clone()).export_words(size, order, endian);
+                assert_eq!(BigInt::import_words(&words, size, order, endian), x);
+            }
+        }
+    }
+}
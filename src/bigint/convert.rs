@@ -1,42 +1,280 @@
-use std::convert::From;
+use std::convert::{From, TryFrom};
 
-use bigint::{Sign, BigInt};
-use bigint::digit::BigDigit;
+use bigint::{BigInt, Limb, Sign};
+use bigint::errors::{TryFromBigIntError, TryFromFloatError};
+use bigint::ops::scalar::{scalar_digits, small_magnitude, to_u128, Primitive};
 
 
 
-macro_rules! impl_from_unsigned {
+macro_rules! impl_from_primitive {
     ($($x:ty),*) => { $(
         impl From<$x> for BigInt {
-            fn from(src: $x) -> BigInt {
-                BigInt {sign: Sign::Positive, digits: vec![src as BigDigit]}
+            #[inline]
+            fn from(src: $x) -> BigInt { src.to_scalar().into_bigint() }
+        }
+        )*
+    }
+}
+
+impl_from_primitive!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Returns the low 128 bits of the two's complement representation of `src`.
+fn low_u128<L: Limb>(src: &BigInt<L>) -> u128 {
+    let low = to_u128(&src.digits[..src.digits.len().min(scalar_digits::<L>())]);
+    if src.is_negative() {
+        low.wrapping_neg()
+    } else {
+        low
+    }
+}
+
+macro_rules! impl_try_from_unsigned {
+    ($($x:ty, $wrapping:ident);*) => { $(
+        impl<'a, L: Limb> TryFrom<&'a BigInt<L>> for $x {
+            type Error = TryFromBigIntError;
+            fn try_from(src: &'a BigInt<L>) -> Result<$x, TryFromBigIntError> {
+                match small_magnitude(src) {
+                    Some(m) if !src.is_negative() && m <= <$x>::max_value() as u128 => Ok(m as $x),
+                    _ => Err(TryFromBigIntError(())),
+                }
             }
         }
+
+        impl<L: Limb> BigInt<L> {
+            /// Returns self modulo 2 to the power of the width of the target type, as the
+            /// primitive `as` casts do.
+            pub fn $wrapping(&self) -> $x { low_u128(self) as $x }
+        }
         )*
     }
 }
 
-impl_from_unsigned!(u8, u16, u32);
+macro_rules! impl_try_from_signed {
+    ($($x:ty, $wrapping:ident);*) => { $(
+        impl<'a, L: Limb> TryFrom<&'a BigInt<L>> for $x {
+            type Error = TryFromBigIntError;
+            fn try_from(src: &'a BigInt<L>) -> Result<$x, TryFromBigIntError> {
+                let max = <$x>::max_value() as u128;
+                match small_magnitude(src) {
+                    Some(m) if !src.is_negative() && m <= max => Ok(m as $x),
+                    Some(m) if src.is_negative() && m <= max + 1 => {
+                        Ok((m as i128).wrapping_neg() as $x)
+                    }
+                    _ => Err(TryFromBigIntError(())),
+                }
+            }
+        }
 
-#[cfg(all(target_pointer_width = "64", not(feature = "thicc_ints")))]
-impl From<u64> for BigInt {
-    fn from(src: u64) -> Self {
-        let [lo, hi]: [u32; 2] = unsafe { ::std::mem::transmute(src) };
-        let out = BigInt {
-            sign: Sign::Positive,
-            digits: vec![lo, hi],
-        };
+        impl<L: Limb> BigInt<L> {
+            /// Returns self modulo 2 to the power of the width of the target type, as the
+            /// primitive `as` casts do.
+            pub fn $wrapping(&self) -> $x { low_u128(self) as $x }
+        }
+        )*
+    }
+}
+
+impl_try_from_unsigned!(
+    u8, to_u8_wrapping;
+    u16, to_u16_wrapping;
+    u32, to_u32_wrapping;
+    u64, to_u64_wrapping;
+    u128, to_u128_wrapping;
+    usize, to_usize_wrapping
+);
+
+impl_try_from_signed!(
+    i8, to_i8_wrapping;
+    i16, to_i16_wrapping;
+    i32, to_i32_wrapping;
+    i64, to_i64_wrapping;
+    i128, to_i128_wrapping;
+    isize, to_isize_wrapping
+);
+
+impl<L: Limb> BigInt<L> {
+    /// Returns the nearest f64 to self, rounding ties to even. Values too large for an f64
+    /// become infinite.
+    pub fn to_f64(&self) -> f64 {
+        if self.bits() > 1024 {
+            return signed(self, ::std::f64::INFINITY);
+        }
+        let (top, shift) = top_bits(self);
+        // The product is exact unless it overflows, since the scale is a power of two.
+        let scale = f64::from_bits((1023 + shift as u64) << 52);
+        signed(self, top as f64 * scale)
+    }
+
+    /// Returns the nearest f32 to self, rounding ties to even. Values too large for an f32
+    /// become infinite.
+    pub fn to_f32(&self) -> f32 {
+        if self.bits() > 128 {
+            return signed(self, ::std::f64::INFINITY) as f32;
+        }
+        let (top, shift) = top_bits(self);
+        let scale = f32::from_bits((127 + shift as u32) << 23);
+        let out = top as f32 * scale;
+        if self.is_negative() { -out } else { out }
+    }
+
+    /// Returns `(m, e)` with self equal to `m * 2^e` up to rounding, like C's `frexp`. The
+    /// mantissa `m` has magnitude in `[0.5, 1)` and is rounded to nearest even, so this works
+    /// for values far too large for `to_f64`. Zero gives `(0.0, 0)`.
+    pub fn to_f64_exp(&self) -> (f64, i64) {
+        if self.is_zero() {
+            return (0.0, 0);
+        }
+        let (top, shift) = top_bits(self);
+        let bits = 64 - top.leading_zeros() as i64;
+        let mantissa = top as f64 / 2f64.powi(bits as i32);
+        // Rounding can carry all the way up to 1.0.
+        let (mantissa, bits) = if mantissa == 1.0 { (0.5, bits + 1) } else { (mantissa, bits) };
+        (signed(self, mantissa), bits + shift as i64)
+    }
+}
 
-        out.trimmed()
+/// Returns the top 64 bits of the magnitude of `x` and how far they were shifted down. If any
+/// lower bits are set the lowest returned bit is set as well, so that rounding the result to a
+/// float with fewer than 63 significant bits rounds the full value correctly.
+fn top_bits<L: Limb>(x: &BigInt<L>) -> (u64, usize) {
+    let bits = x.bits();
+    let shift = bits.saturating_sub(64);
+    let mut top = x.abs().extract_bits_u64(shift..bits);
+    match x.trailing_zeros() {
+        Some(zeros) if zeros < shift => top |= 1,
+        _ => {}
     }
+    (top, shift)
+}
+
+fn signed<L: Limb>(x: &BigInt<L>, magnitude: f64) -> f64 {
+    if x.is_negative() { -magnitude } else { magnitude }
 }
 
-#[cfg(feature = "thicc_ints")]
-impl From<u64> for BigInt {
-    fn from(src: u64) -> Self {
-        BigInt {
-            sign: Sign::Positive,
-            digits: vec![src as BigDigit],
+impl TryFrom<f64> for BigInt {
+    type Error = TryFromFloatError;
+    /// Converts a finite float to a BigInt, truncating any fractional part.
+    fn try_from(src: f64) -> Result<BigInt, TryFromFloatError> {
+        if !src.is_finite() {
+            return Err(TryFromFloatError(()));
+        }
+        let int = src.trunc();
+        if int == 0.0 {
+            return Ok(BigInt::zero());
+        }
+
+        let bits = int.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i32 - 1075;
+        let mantissa = BigInt::from((bits & ((1 << 52) - 1)) | (1 << 52));
+        let mut out = if exponent < 0 {
+            mantissa >> (-exponent) as usize
+        } else {
+            mantissa << exponent as usize
+        };
+        if int < 0.0 {
+            out.sign = Sign::Negative;
         }
+        Ok(out)
+    }
+}
+
+impl TryFrom<f32> for BigInt {
+    type Error = TryFromFloatError;
+    /// Converts a finite float to a BigInt, truncating any fractional part.
+    fn try_from(src: f32) -> Result<BigInt, TryFromFloatError> { BigInt::try_from(src as f64) }
+}
+
+
+#[test]
+fn from_primitive_test() {
+    assert_eq!(BigInt::from(0i32), BigInt::zero());
+    assert_eq!(BigInt::from(-1i8), -BigInt::one());
+    assert_eq!(BigInt::from(::std::i128::MIN), -(BigInt::one() << 127usize));
+    assert_eq!(BigInt::from(::std::u128::MAX), (BigInt::one() << 128usize) - 1u8);
+    assert_eq!(BigInt::from(::std::isize::MIN), -BigInt::from(::std::isize::MIN as usize));
+    assert_eq!(BigInt::from(0x1234_5678_9abc_def0u64).limbs_u64(), vec![0x1234_5678_9abc_def0]);
+}
+
+#[test]
+fn try_from_test() {
+    let values = [-(1i128 << 100), -129, -128, -1, 0, 1, 127, 128, 255, 256, 65535, 1 << 100];
+    for &v in values.iter() {
+        let big = BigInt::from(v);
+        assert_eq!(u8::try_from(&big).ok(), u8::try_from(v).ok());
+        assert_eq!(i8::try_from(&big).ok(), i8::try_from(v).ok());
+        assert_eq!(u16::try_from(&big).ok(), u16::try_from(v).ok());
+        assert_eq!(i64::try_from(&big).ok(), i64::try_from(v).ok());
+        assert_eq!(u128::try_from(&big).ok(), u128::try_from(v).ok());
+        assert_eq!(i128::try_from(&big), Ok(v));
+    }
+
+    let too_big = BigInt::one() << 128usize;
+    assert_eq!(u128::try_from(&too_big), Err(TryFromBigIntError(())));
+    assert!(i128::try_from(&-(BigInt::one() << 127usize)).is_ok());
+    assert!(i128::try_from(&(BigInt::one() << 127usize)).is_err());
+}
+
+#[test]
+fn wrapping_test() {
+    let x = -(BigInt::from(0x1234_5678_9abc_def0u64) << 128usize) - 0x1ffu32;
+    assert_eq!(x.to_u8_wrapping(), (-0x1ffi32) as u8);
+    assert_eq!(x.to_i16_wrapping(), -0x1ff);
+    assert_eq!(x.to_u64_wrapping(), (-0x1ffi64) as u64);
+    assert_eq!(x.to_i128_wrapping(), -0x1ff);
+    assert_eq!((BigInt::one() << 200usize).to_u32_wrapping(), 0);
+    assert_eq!(BigInt::from(300u16).to_i8_wrapping(), 300u16 as i8);
+}
+
+#[test]
+fn to_float_test() {
+    use rand::{thread_rng, Rng};
+    let mut rng = thread_rng();
+    for _ in 0..2000 {
+        let v = (rng.gen::<u64>() as i128) << 64 | rng.gen::<u64>() as i128;
+        let v = v >> rng.gen_range(0, 128);
+        assert_eq!(BigInt::from(v).to_f64(), v as f64);
+        assert_eq!(BigInt::from(v).to_f32(), v as f32);
+        let u = v as u128;
+        assert_eq!(BigInt::from(u).to_f64(), u as f64);
+        assert_eq!(BigInt::from(u).to_f32(), u as f32);
     }
+
+    // Halfway cases round to even, and anything above halfway rounds up.
+    let one = BigInt::one();
+    let half_ulp = &one << 200usize;
+    let next_up = 2f64.powi(253) * (1.0 + 2f64.powi(-52));
+    assert_eq!(((&one << 253usize) + &half_ulp).to_f64(), 2f64.powi(253));
+    assert_eq!(((&one << 253usize) + &half_ulp + 1u8).to_f64(), next_up);
+    assert_eq!(((&one << 253usize) + &half_ulp * 3u8).to_f64(), next_up * (1.0 + 2f64.powi(-52)));
+
+    assert_eq!((&one << 1023usize).to_f64(), 2f64.powi(1023));
+    assert_eq!(((&one << 1024usize) - 1u8).to_f64(), ::std::f64::INFINITY);
+    assert_eq!((-(&one << 5000usize)).to_f64(), ::std::f64::NEG_INFINITY);
+    assert_eq!((&one << 128usize).to_f32(), ::std::f32::INFINITY);
+    assert_eq!(BigInt::zero().to_f64(), 0.0);
+}
+
+#[test]
+fn to_f64_exp_test() {
+    let one = BigInt::one();
+    assert_eq!(BigInt::zero().to_f64_exp(), (0.0, 0));
+    assert_eq!(one.to_f64_exp(), (0.5, 1));
+    assert_eq!(BigInt::from(-12i8).to_f64_exp(), (-0.75, 4));
+    assert_eq!((&one << 5000usize).to_f64_exp(), (0.5, 5001));
+    assert_eq!(((&one << 5000usize) - 1u8).to_f64_exp(), (0.5, 5001));
+    assert_eq!((BigInt::from(3u8) << 5000usize).to_f64_exp(), (0.75, 5002));
+}
+
+#[test]
+fn from_float_test() {
+    assert_eq!(BigInt::try_from(0.0), Ok(BigInt::zero()));
+    assert_eq!(BigInt::try_from(-0.9), Ok(BigInt::zero()));
+    assert_eq!(BigInt::try_from(-2.5), Ok(BigInt::from(-2i8)));
+    assert_eq!(BigInt::try_from(4503599627370497.0), Ok(BigInt::from(4503599627370497u64)));
+    assert_eq!(BigInt::try_from(1e300).unwrap().to_f64(), 1e300);
+    assert_eq!(BigInt::try_from(-1.5e30f32).unwrap().to_f32(), -1.5e30);
+    assert_eq!(BigInt::try_from(2f64.powi(100)), Ok(BigInt::one() << 100usize));
+    assert!(BigInt::try_from(::std::f64::NAN).is_err());
+    assert!(BigInt::try_from(::std::f64::INFINITY).is_err());
+    assert!(BigInt::try_from(::std::f32::NEG_INFINITY).is_err());
 }
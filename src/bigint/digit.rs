@@ -1,104 +1,200 @@
-pub use self::digit::{chunk_size_from_radix, constants, BigDigit, DoubleBigDigit};
-
-
-#[cfg(feature = "thicc_ints")]
-mod digit {
-
-    pub type BigDigit = u64;
-    pub type DoubleBigDigit = u128;
-
-    pub fn chunk_size_from_radix(radix: u32) -> Option<usize> {
-        use self::constants::*;
-        match radix {
-            2 => Some(BASE_2_PARSE_CHUNK_SIZE),
-            8 => Some(BASE_8_PARSE_CHUNK_SIZE),
-            10 => Some(BASE_10_PARSE_CHUNK_SIZE),
-            16 => Some(BASE_16_PARSE_CHUNK_SIZE),
-            _ => None,
-        }
-    }
-
-    pub mod constants {
-        pub const DIGIT_MAX: u64 = ::std::u64::MAX;
-        pub const DIGIT_SIZE: usize = 64;
-
-        pub const BASE_2_PARSE_CHUNK_SIZE: usize = 62;
-        pub const BASE_8_PARSE_CHUNK_SIZE: usize = 20;
-        pub const BASE_10_PARSE_CHUNK_SIZE: usize = 16;
-        pub const BASE_16_PARSE_CHUNK_SIZE: usize = 16;
-
-        pub const BASE_10_FORMAT_CHUNK_SIZE: u64 = 1_000_000_000_000_000_000_0;
-        pub const BASE_10_FORMAT_PAD_SIZE: usize = 19;
-    }
-
+use std::fmt::{Binary, Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, Mul, Not,
+               Rem, Shl, Shr, Sub};
+
+pub use self::default::DefaultLimb;
+
+/// The default limb type, under the name it had before limbs became generic.
+pub type BigDigit = DefaultLimb;
+
+// u64 limbs are the default on 64 bit targets, where u128 products are cheap. The thin_ints
+// feature selects u32 limbs instead, unless thicc_ints asks for u64 limbs explicitly.
+#[cfg(any(feature = "thicc_ints", all(target_pointer_width = "64", not(feature = "thin_ints"))))]
+mod default {
+    /// The limb type of a BigInt when none is given.
+    pub type DefaultLimb = u64;
 }
 
-#[cfg(all(target_pointer_width = "64", not(feature = "thicc_ints")))]
-mod digit {
+// u32 limbs are used on 32 bit targets as well. Their u64 double digits are emulated there, but
+// that is still much faster than u16 limbs would be.
+#[cfg(not(any(
+    feature = "thicc_ints",
+    all(target_pointer_width = "64", not(feature = "thin_ints"))
+)))]
+mod default {
+    /// The limb type of a BigInt when none is given.
+    pub type DefaultLimb = u32;
+}
 
-    pub type BigDigit = u32;
-    pub type DoubleBigDigit = u64;
+mod private {
+    pub trait Sealed {}
+}
 
-    pub fn chunk_size_from_radix(radix: u32) -> Option<usize> {
-        use self::constants::*;
-        match radix {
-            2 => Some(BASE_2_PARSE_CHUNK_SIZE),
-            8 => Some(BASE_8_PARSE_CHUNK_SIZE),
-            10 => Some(BASE_10_PARSE_CHUNK_SIZE),
-            16 => Some(BASE_16_PARSE_CHUNK_SIZE),
-            _ => None,
+/// An unsigned integer type that a BigInt can store its magnitude in. This is implemented for
+/// u16, u32 and u64, and can't be implemented outside of this crate.
+pub trait Limb:
+    private::Sealed
+    + Copy
+    + Debug
+    + Display
+    + Binary
+    + Eq
+    + Ord
+    + Hash
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+    + BitAndAssign
+    + BitOrAssign
+    + BitXorAssign
+    + 'static {
+    /// An unsigned type twice as wide, which can hold the product of two limbs plus two more.
+    type Double: Copy
+        + Ord
+        + From<Self>
+        + Add<Output = Self::Double>
+        + Sub<Output = Self::Double>
+        + Mul<Output = Self::Double>
+        + Div<Output = Self::Double>
+        + Rem<Output = Self::Double>
+        + Shl<usize, Output = Self::Double>
+        + Shr<usize, Output = Self::Double>;
+
+    /// The width of a limb in bits.
+    const BITS: usize;
+    /// The limb with no bits set.
+    const ZERO: Self;
+    /// The limb with only the lowest bit set.
+    const ONE: Self;
+    /// The limb with every bit set.
+    const MAX: Self;
+
+    /// Splits a double limb into its low and high halves.
+    fn to_lo_hi(d: Self::Double) -> [Self; 2];
+
+    /// Joins low and high halves into a double limb.
+    fn from_lo_hi(lh: [Self; 2]) -> Self::Double;
+
+    /// Returns the low bits of `x`.
+    fn truncate(x: u128) -> Self;
+
+    /// Zero extends self to a u128.
+    fn to_u128(self) -> u128;
+
+    /// As the primitive method of the same name.
+    fn overflowing_add(self, rhs: Self) -> (Self, bool);
+    /// As the primitive method of the same name.
+    fn overflowing_sub(self, rhs: Self) -> (Self, bool);
+    /// As the primitive method of the same name.
+    fn wrapping_add(self, rhs: Self) -> Self;
+    /// As the primitive method of the same name.
+    fn wrapping_sub(self, rhs: Self) -> Self;
+    /// As the primitive method of the same name.
+    fn leading_zeros(self) -> u32;
+    /// As the primitive method of the same name.
+    fn trailing_zeros(self) -> u32;
+    /// As the primitive method of the same name.
+    fn count_ones(self) -> u32;
+    /// As the primitive method of the same name.
+    fn is_power_of_two(self) -> bool;
+
+    /// Zero extends self to a double limb.
+    #[inline]
+    fn widen(self) -> Self::Double { Self::Double::from(self) }
+
+    /// Returns how many digits of the given radix are parsed into a limb at a time, which is
+    /// the largest `k` with `radix^k` fitting in a limb, or `None` unless `radix` is in
+    /// `2..=36`.
+    fn chunk_size_from_radix(radix: u32) -> Option<usize> {
+        if !(2..=36).contains(&radix) {
+            return None;
         }
-    }
-
-    pub mod constants {
-        pub const DIGIT_MAX: u32 = ::std::u32::MAX;
-        pub const DIGIT_SIZE: usize = 32;
-
-        pub const BASE_2_PARSE_CHUNK_SIZE: usize = 31;
-        pub const BASE_8_PARSE_CHUNK_SIZE: usize = 10;
-        pub const BASE_10_PARSE_CHUNK_SIZE: usize = 8;
-        pub const BASE_16_PARSE_CHUNK_SIZE: usize = 8;
-
-        pub const BASE_10_FORMAT_CHUNK_SIZE: u32 = 1_000_000_000;
-        pub const BASE_10_FORMAT_PAD_SIZE: usize = 9;
-
+        let (radix, max) = (radix as u128, Self::MAX.to_u128());
+        let (mut k, mut power) = (0, radix);
+        while power <= max {
+            power *= radix;
+            k += 1;
+        }
+        Some(k)
     }
 }
 
-#[cfg(all(target_pointer_width = "32", not(feature = "thicc_ints")))]
-mod digit {
-
-    pub type BigDigit = u16;
-    pub type DoubleBigDigit = u32;
-
-    pub mod constants {
-        pub const DIGIT_MAX: u16 = ::std::u16::MAX;
-        pub const DIGIT_SIZE: usize = 16;
-        pub const BASE_10_PARSE_CHUNK_SIZE: usize = 4;
+macro_rules! impl_limb {
+    ($t:ty, $double:ty) => {
+        impl private::Sealed for $t {}
+
+        impl Limb for $t {
+            type Double = $double;
+
+            const BITS: usize = <$t>::BITS as usize;
+            const ZERO: $t = 0;
+            const ONE: $t = 1;
+            const MAX: $t = <$t>::MAX;
+
+            #[inline]
+            fn to_lo_hi(d: $double) -> [$t; 2] { [d as $t, (d >> <$t>::BITS) as $t] }
+
+            #[inline]
+            fn from_lo_hi(lh: [$t; 2]) -> $double {
+                (lh[0] as $double) | ((lh[1] as $double) << <$t>::BITS)
+            }
+
+            #[inline]
+            fn truncate(x: u128) -> $t { x as $t }
+            #[inline]
+            fn to_u128(self) -> u128 { self as u128 }
+
+            #[inline]
+            fn overflowing_add(self, rhs: $t) -> ($t, bool) { <$t>::overflowing_add(self, rhs) }
+            #[inline]
+            fn overflowing_sub(self, rhs: $t) -> ($t, bool) { <$t>::overflowing_sub(self, rhs) }
+            #[inline]
+            fn wrapping_add(self, rhs: $t) -> $t { <$t>::wrapping_add(self, rhs) }
+            #[inline]
+            fn wrapping_sub(self, rhs: $t) -> $t { <$t>::wrapping_sub(self, rhs) }
+            #[inline]
+            fn leading_zeros(self) -> u32 { <$t>::leading_zeros(self) }
+            #[inline]
+            fn trailing_zeros(self) -> u32 { <$t>::trailing_zeros(self) }
+            #[inline]
+            fn count_ones(self) -> u32 { <$t>::count_ones(self) }
+            #[inline]
+            fn is_power_of_two(self) -> bool { <$t>::is_power_of_two(self) }
+        }
     }
 }
 
-#[inline]
-pub(crate) fn to_lo_hi(d: DoubleBigDigit) -> [BigDigit; 2] {
-    use self::constants::DIGIT_SIZE;
-
-    [ d as BigDigit, (d >> DIGIT_SIZE) as BigDigit ]
-}
-
-#[inline]
-pub(crate) fn from_lo_hi(lh: [BigDigit; 2]) -> DoubleBigDigit {
-    use self::constants::DIGIT_SIZE;
-
-    (lh[0] as DoubleBigDigit) + ((lh[1] as DoubleBigDigit) << DIGIT_SIZE)
-}
+impl_limb!(u16, u32);
+impl_limb!(u32, u64);
+impl_limb!(u64, u128);
 
 
 #[test]
 fn lo_hi_digit_test() {
-    use bigint::digit::constants::DIGIT_SIZE;
-    use bigint::digit::to_lo_hi;
-    let mut a: DoubleBigDigit = 2;
-    a = a.pow(DIGIT_SIZE as u32 + 2);
-
-    assert_eq!([0, 4], to_lo_hi(a));
+    fn check<L: Limb>() {
+        let d = L::from_lo_hi([L::MAX, L::ONE + L::ONE]);
+        assert_eq!(L::to_lo_hi(d), [L::MAX, L::ONE + L::ONE]);
+        assert!(L::to_lo_hi(L::MAX.widen() * L::MAX.widen())[1] == L::MAX - L::ONE);
+    }
+    check::<u16>();
+    check::<u32>();
+    check::<u64>();
+
+    assert_eq!(u64::chunk_size_from_radix(16), Some(15));
+    assert_eq!(u32::chunk_size_from_radix(10), Some(9));
+    assert_eq!(u16::chunk_size_from_radix(2), Some(15));
+    assert_eq!(u64::chunk_size_from_radix(36), Some(12));
+    assert_eq!(u64::chunk_size_from_radix(37), None);
+
+    let a: u128 = 2u128.pow(64 + 2);
+    assert_eq!([0, 4], u64::to_lo_hi(a));
 }
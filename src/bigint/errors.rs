@@ -1,7 +1,61 @@
+use std::error::Error;
+use std::fmt;
+
+/// The error returned when parsing a BigInt from a string fails.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum BigIntParseError {
-    InvalidCharacters,
+    /// The string was empty or only whitespace.
     EmptyInput,
+    /// The sign and radix prefix were not followed by any digits, as in `"-"` or `"0x"`.
+    NoDigits,
+    /// The character `found`, starting at byte `offset` of the string, is not a digit of the
+    /// radix being parsed.
+    InvalidDigit {
+        /// The byte offset of the character in the string.
+        offset: usize,
+        /// The offending character.
+        found: char,
+    },
+    /// The radix is not supported.
     InvalidRadix,
-    Unknown,
 }
+
+impl fmt::Display for BigIntParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BigIntParseError::EmptyInput => f.write_str("cannot parse BigInt from empty string"),
+            BigIntParseError::NoDigits => f.write_str("no digits after sign or radix prefix"),
+            BigIntParseError::InvalidDigit { offset, found } => {
+                write!(f, "invalid digit {:?} at byte {}", found, offset)
+            }
+            BigIntParseError::InvalidRadix => f.write_str("invalid radix"),
+        }
+    }
+}
+
+impl Error for BigIntParseError {}
+
+/// The error returned when a BigInt is out of range of the primitive integer type it is being
+/// converted to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TryFromBigIntError(pub(crate) ());
+
+impl fmt::Display for TryFromBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("BigInt out of range of the target integer type")
+    }
+}
+
+impl Error for TryFromBigIntError {}
+
+/// The error returned when converting a NaN or infinite float to a BigInt.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TryFromFloatError(pub(crate) ());
+
+impl fmt::Display for TryFromFloatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("cannot convert NaN or infinity to BigInt")
+    }
+}
+
+impl Error for TryFromFloatError {}
@@ -0,0 +1,164 @@
+use bigint::BigInt;
+use bigint::ops::mul::product_tree;
+use bigint::primes::primes_up_to;
+
+/// Combinatorial functions. Each result is assembled from its prime factorization, found with
+/// Legendre's formula, so that all of the large multiplications are balanced.
+impl BigInt {
+    /// Returns `n!`.
+    pub fn factorial(n: u32) -> BigInt {
+        let factors = primes_up_to(n).into_iter().map(|p| (p, legendre(n, p))).collect();
+        from_factorization(factors)
+    }
+
+    /// Returns `n!!`, the product of every integer from `n` down to 1 with the same parity as
+    /// `n`.
+    pub fn double_factorial(n: u32) -> BigInt {
+        let m = n / 2;
+        if n == 2 * m {
+            // (2m)!! = 2^m * m!
+            return BigInt::factorial(m) << m as usize;
+        }
+        // (2m + 1)!! = (2m + 1)! / (2^m * m!), which has no factor of two.
+        let factors = primes_up_to(n)
+            .into_iter()
+            .skip(1)
+            .map(|p| (p, legendre(n, p) - legendre(m, p)))
+            .collect();
+        from_factorization(factors)
+    }
+
+    /// Returns the binomial coefficient `n` choose `k`, which is zero if `k > n`.
+    pub fn binomial(n: u32, k: u32) -> BigInt {
+        if k > n {
+            BigInt::zero()
+        } else {
+            BigInt::multinomial(&[k, n - k])
+        }
+    }
+
+    /// Returns the multinomial coefficient `(k1 + k2 + ...)! / (k1! * k2! * ...)`. Panics if the
+    /// sum of `ks` does not fit in a u32.
+    pub fn multinomial(ks: &[u32]) -> BigInt {
+        let n = ks
+            .iter()
+            .try_fold(0u32, |acc, &k| acc.checked_add(k))
+            .expect("Multinomial total is too large");
+        let factors = primes_up_to(n)
+            .into_iter()
+            .map(|p| (p, legendre(n, p) - ks.iter().map(|&k| legendre(k, p)).sum::<u64>()))
+            .collect();
+        from_factorization(factors)
+    }
+
+    /// Returns the product of all primes less than or equal to `n`.
+    pub fn primorial(n: u32) -> BigInt {
+        product_tree(pack_factors(primes_up_to(n)))
+    }
+}
+
+/// Returns the exponent of the prime `p` in `n!`.
+fn legendre(n: u32, p: u32) -> u64 {
+    let (mut n, mut total) = (n as u64, 0);
+    while n > 0 {
+        n /= p as u64;
+        total += n;
+    }
+    total
+}
+
+/// Multiplies small factors together into as few u64 sized BigInts as possible.
+fn pack_factors(factors: Vec<u32>) -> Vec<BigInt> {
+    let mut out = Vec::new();
+    let mut acc = 1u64;
+    for f in factors {
+        match acc.checked_mul(f as u64) {
+            Some(x) => acc = x,
+            None => {
+                out.push(BigInt::from(acc));
+                acc = f as u64;
+            }
+        }
+    }
+    out.push(BigInt::from(acc));
+    out
+}
+
+/// Returns the product of `p^e` over `(p, e)` in `factors`. The odd part is built by binary
+/// exponentiation over all primes at once: each bit of the exponents, from the top, squares the
+/// accumulator and multiplies in the primes whose exponent has that bit set. Powers of two are
+/// applied with a single shift.
+fn from_factorization(factors: Vec<(u32, u64)>) -> BigInt {
+    let twos = factors.iter().find(|f| f.0 == 2).map_or(0, |f| f.1);
+    let odd: Vec<(u32, u64)> = factors.into_iter().filter(|f| f.0 != 2 && f.1 != 0).collect();
+    let top = odd.iter().map(|f| f.1).max().unwrap_or(0);
+
+    let mut acc = BigInt::one();
+    for bit in (0..64 - top.leading_zeros()).rev() {
+        acc = &acc * &acc;
+        let group = odd.iter().filter(|f| (f.1 >> bit) & 1 == 1).map(|f| f.0).collect();
+        acc *= product_tree(pack_factors(group));
+    }
+    acc << twos as usize
+}
+
+
+#[test]
+fn legendre_test() {
+    assert_eq!(legendre(100, 5), 24);
+}
+
+#[test]
+fn factorial_test() {
+    let mut expected = BigInt::one();
+    for n in 0..300u32 {
+        if n > 0 {
+            expected *= n;
+        }
+        assert_eq!(BigInt::factorial(n), expected);
+    }
+
+    let mut expected = [BigInt::one(), BigInt::one()];
+    for n in 0..300u32 {
+        if n > 1 {
+            expected[n as usize % 2] *= n;
+        }
+        assert_eq!(BigInt::double_factorial(n), expected[n as usize % 2]);
+    }
+}
+
+#[test]
+fn binomial_test() {
+    let mut row = vec![BigInt::one()];
+    for n in 0..200u32 {
+        for k in 0..=n {
+            assert_eq!(BigInt::binomial(n, k), row[k as usize]);
+        }
+        assert!(BigInt::binomial(n, n + 1).is_zero());
+
+        let mut next = vec![BigInt::one()];
+        next.extend(row.windows(2).map(|w| &w[0] + &w[1]));
+        next.push(BigInt::one());
+        row = next;
+    }
+}
+
+#[test]
+fn multinomial_test() {
+    assert_eq!(BigInt::multinomial(&[]), BigInt::one());
+    assert_eq!(BigInt::multinomial(&[0, 5]), BigInt::one());
+    assert_eq!(BigInt::multinomial(&[1, 4, 4, 2]), BigInt::from(34650u32));
+    assert_eq!(
+        BigInt::multinomial(&[100, 200, 300]),
+        BigInt::binomial(600, 100) * BigInt::binomial(500, 200)
+    );
+}
+
+#[test]
+fn primorial_test() {
+    assert_eq!(BigInt::primorial(0), BigInt::one());
+    assert_eq!(BigInt::primorial(10), BigInt::from(210u8));
+    assert_eq!(BigInt::primorial(30), BigInt::from(6469693230u64));
+    let expected = primes_up_to(1000).into_iter().fold(BigInt::one(), |acc, p| acc * p);
+    assert_eq!(BigInt::primorial(1000), expected);
+}
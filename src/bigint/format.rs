@@ -1,75 +1,203 @@
-use std::fmt::{Binary, Display, Error, Formatter};
+use std::cmp::Ordering;
+use std::fmt::{Binary, Display, Error, Formatter, LowerExp, LowerHex, Octal, UpperExp, UpperHex};
 
-use bigint::BigInt;
-use bigint::sign::Sign::*;
-use bigint::digit::constants::*;
+use bigint::{BigInt, Limb};
+use bigint::sign::Sign;
+use bigint::ops::div::ddiv;
 
 
-impl Binary for BigInt {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        match self.sign {
-            Positive => {
-                if f.sign_plus() {
-                    write!(f, "+")?
+// Like the primitive integers, all of these honor the width, fill, alignment, sign and zero
+// padding flags. Negative numbers are written as a sign and magnitude in every radix.
+macro_rules! impl_fmt_radix {
+    ($($trait:ident, $radix:expr, $prefix:expr, $upper:expr);*) => { $(
+        impl<L: Limb> $trait for BigInt<L> {
+            fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+                let mut digits = radix_digits(self, $radix);
+                if $upper {
+                    digits.make_ascii_uppercase();
                 }
-            }
-            Negative => write!(f, "-")?,
-            Zero => {
-                write!(f, "0")?;
-                return Ok(())
+                f.pad_integral(!self.is_negative(), $prefix, &digits)
             }
         }
+        )*
+    }
+}
 
-        if f.alternate() {
-            write!(f, "0b")?
-        }
+impl_fmt_radix!(
+    Display, 10, "", false;
+    Binary, 2, "0b", false;
+    Octal, 8, "0o", false;
+    LowerHex, 16, "0x", false;
+    UpperHex, 16, "0x", true
+);
 
-        let mut i = self.digits.iter().rev();
+impl<L: Limb> LowerExp for BigInt<L> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> { fmt_exp(self, f, 'e') }
+}
 
-        let first = i.next().unwrap();
-        write!(f, "{:b}", first)?;
+impl<L: Limb> UpperExp for BigInt<L> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> { fmt_exp(self, f, 'E') }
+}
 
-        for d in i {
-            write!(f, "{:0>d_size$b}", d, d_size = DIGIT_SIZE)?;
+impl<L: Limb> BigInt<L> {
+    /// Returns self written in `radix` with lowercase digits and a leading `-` if negative.
+    /// Panics unless `radix` is in `2..=36`.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "Radix must be in 2..=36");
+        let digits = radix_digits(self, radix);
+        if self.is_negative() {
+            format!("-{}", digits)
+        } else {
+            digits
         }
-
-        Ok(())
     }
 }
 
-impl Display for BigInt {
-    fn fmt(&self, f: &mut Formatter) ->Result<(), Error> {
-        match self.sign {
-            Positive => {
-                if f.sign_plus() {
-                    write!(f, "+")?
-                }
+/// Writes `x` in scientific notation as the primitive integers do, like `1.2345e678`. The
+/// mantissa has no trailing zeroes unless a precision is given, in which case it is rounded to
+/// that many decimals with ties to even.
+fn fmt_exp<L: Limb>(x: &BigInt<L>, f: &mut Formatter, e: char) -> Result<(), Error> {
+    let digits = radix_digits(x, 10).into_bytes();
+    let mut exponent = digits.len() - 1;
+    let mut mantissa = digits.clone();
+
+    match f.precision() {
+        None => {
+            while mantissa.len() > 1 && mantissa.last() == Some(&b'0') {
+                mantissa.pop();
             }
-            Negative => write!(f, "-")?,
-            Zero => {
-                write!(f, "0")?;
-                return Ok(())
+        }
+        Some(precision) if precision < exponent => {
+            mantissa.truncate(precision + 1);
+            let rest = &digits[precision + 1..];
+            let round_up = match rest[0].cmp(&b'5') {
+                Ordering::Greater => true,
+                Ordering::Less => false,
+                Ordering::Equal => {
+                    rest[1..].iter().any(|&d| d != b'0') || mantissa[precision] & 1 == 1
+                }
+            };
+            if round_up {
+                let mut i = mantissa.len();
+                while i > 0 && mantissa[i - 1] == b'9' {
+                    mantissa[i - 1] = b'0';
+                    i -= 1;
+                }
+                if i == 0 {
+                    // All nines carried out into a new leading digit.
+                    mantissa[0] = b'1';
+                    exponent += 1;
+                } else {
+                    mantissa[i - 1] += 1;
+                }
             }
         }
+        Some(precision) => mantissa.resize(precision + 1, b'0'),
+    }
 
-        let mut digits = vec![];
-        let mut reduced = self.clone();
+    let mut out = String::with_capacity(mantissa.len() + 24);
+    out.push(mantissa[0] as char);
+    if mantissa.len() > 1 {
+        out.push('.');
+        out.extend(mantissa[1..].iter().map(|&d| d as char));
+    }
+    out.push(e);
+    out.push_str(&exponent.to_string());
+    f.pad_integral(!x.is_negative(), "", &out)
+}
+
+/// Returns the magnitude of `x` in `radix`, most significant digit first, in lowercase. Zero is
+/// written as `0`.
+fn radix_digits<L: Limb>(x: &BigInt<L>, radix: u32) -> String {
+    if x.is_zero() {
+        return String::from("0");
+    }
 
-        while !reduced.is_zero() {
-            digits.push(&reduced % BASE_10_FORMAT_CHUNK_SIZE);
-            reduced = &reduced / BASE_10_FORMAT_CHUNK_SIZE;
+    // Digit values, least significant first.
+    let mut values: Vec<u8> = Vec::new();
+    if radix.is_power_of_two() {
+        let bits = radix.trailing_zeros() as usize;
+        let (mut acc, mut acc_bits) = (0u128, 0);
+        for &d in x.digits.iter() {
+            acc |= d.to_u128() << acc_bits;
+            acc_bits += L::BITS;
+            while acc_bits >= bits {
+                values.push((acc & (radix as u128 - 1)) as u8);
+                acc >>= bits;
+                acc_bits -= bits;
+            }
         }
+        values.push(acc as u8);
+    } else {
+        let chunk_size = L::chunk_size_from_radix(radix).unwrap();
+        for chunk in radix_chunks(x, L::truncate((radix as u128).pow(chunk_size as u32))) {
+            let mut chunk = chunk.to_u128();
+            for _ in 0..chunk_size {
+                values.push((chunk % radix as u128) as u8);
+                chunk /= radix as u128;
+            }
+        }
+    }
 
-        let mut i = digits.into_iter().rev();
+    while values.last() == Some(&0) {
+        values.pop();
+    }
+    values
+        .iter()
+        .rev()
+        .map(|&d| ::std::char::from_digit(d as u32, radix).unwrap())
+        .collect()
+}
 
-        write!(f, "{}", i.next().unwrap())?;
+/// Below this many limbs, decimal chunks are split off one at a time by short division.
+const SPLIT_THRESHOLD: usize = 32;
 
-        for digit_chunk in i {
-            write!(f, "{:0>d_size$}", digit_chunk, d_size = BASE_10_FORMAT_PAD_SIZE)?;
-        }
+/// Returns the magnitude of the nonzero `x` in base `base`, least significant chunk first and
+/// without leading zero chunks.
+fn radix_chunks<L: Limb>(x: &BigInt<L>, base: L) -> Vec<L> {
+    let magnitude = x.abs();
 
-        Ok(())
+    // powers[k] is the chunk base raised to 2^k, up to the first one above the magnitude.
+    let mut powers = vec![BigInt::from_sign_limbs(Sign::Positive, vec![base])];
+    while *powers.last().unwrap() <= magnitude {
+        let next = powers.last().unwrap() * powers.last().unwrap();
+        powers.push(next);
     }
+
+    let mut out = Vec::new();
+    let level = powers.len() - 1;
+    split_chunks(magnitude, level, false, &powers, &mut out);
+    while out.len() > 1 && out.last() == Some(&L::ZERO) {
+        out.pop();
+    }
+    out
+}
+
+/// Appends the chunks of `x < powers[level]` to `out`, least significant first. If `pad` is set
+/// exactly `2^level` chunks are written, including leading zeroes. Otherwise the high half is
+/// split off by one division by `powers[level - 1]` and both halves are converted recursively.
+fn split_chunks<L: Limb>(
+    mut x: BigInt<L>,
+    level: usize,
+    pad: bool,
+    powers: &[BigInt<L>],
+    out: &mut Vec<L>,
+) {
+    if level == 0 || x.digits.len() <= SPLIT_THRESHOLD {
+        let start = out.len();
+        while !x.is_zero() {
+            out.push(ddiv(&mut x.digits, powers[0].digits[0]));
+            x.trim();
+        }
+        if pad {
+            out.resize(start + (1 << level), L::ZERO);
+        }
+        return;
+    }
+
+    let (high, low) = x.div_mod(&powers[level - 1]);
+    split_chunks(low, level - 1, true, powers, out);
+    split_chunks(high, level - 1, pad, powers, out);
 }
 
 #[test]
@@ -89,3 +217,83 @@ fn display_involution_test() {
     let s_toint_tostring = format!("{}", s_toint);
     assert_eq!(s, s_toint_tostring);
 }
+
+#[test]
+fn large_display_test() {
+    use rand::{thread_rng, Rng};
+    use std::str::FromStr;
+    fn check<L: Limb>(s: &str) {
+        let x: BigInt<L> = BigInt::from_str(s).unwrap().convert_limbs();
+        assert_eq!(x.to_string(), s);
+        assert_eq!((-x).to_string(), format!("-{}", s));
+    }
+
+    let mut cases = vec![
+        "9".repeat(3000),
+        format!("1{}", "0".repeat(3000)),
+        format!("1{}1", "0".repeat(2500)),
+        format!("{}{}", "123456789".repeat(200), "0".repeat(1500)),
+    ];
+    let mut rng = thread_rng();
+    for &len in [1, 19, 20, 700, 4321].iter() {
+        let digits: String = (1..len).map(|_| rng.gen_range(b'0', b'9' + 1) as char).collect();
+        cases.push(format!("{}{}", rng.gen_range(1, 10), digits));
+    }
+    for s in cases.iter() {
+        check::<u16>(s);
+        check::<u32>(s);
+        check::<u64>(s);
+    }
+}
+
+#[test]
+fn format_options_test() {
+    macro_rules! check {
+        ($v:expr, $($spec:expr),*) => { $(
+            assert_eq!(format!($spec, BigInt::from($v)), format!($spec, $v), "{}", $spec);
+        )* }
+    }
+    for &v in [0i64, 1, -1, 7, -12345, 1200, 9999, 125, 135, 1 << 40, -(1 << 62)].iter() {
+        check!(v, "{}", "{:>40}", "{:<12}|", "{:^13}", "{:08}", "{:+}", "{:*^+20}", "{:+015}");
+        check!(v, "{:e}", "{:E}", "{:.0e}", "{:.1e}", "{:.3e}", "{:.30e}", "{:012.2e}", "{:>12e}");
+    }
+    for &v in [0u64, 1, 8, 255, 0xdead_beef, ::std::u64::MAX].iter() {
+        check!(v, "{:b}", "{:#b}", "{:o}", "{:#o}", "{:x}", "{:#x}", "{:X}", "{:#X}");
+        check!(v, "{:#010x}", "{:+#x}", "{:08b}", "{:^9o}", "{:>30X}", "{:#066b}");
+    }
+    check!(::std::i128::MIN, "{}", "{:e}", "{:.5e}", "{:E}");
+    check!(::std::u128::MAX, "{:x}", "{:o}", "{:b}", "{:.10e}", "{:+e}");
+
+    let x = -(BigInt::from(0xabcdefu32) << 100usize);
+    assert_eq!(format!("{:#x}", x), format!("-0xabcdef{}", "0".repeat(25)));
+    assert_eq!(format!("{:X}", x), format!("-ABCDEF{}", "0".repeat(25)));
+    assert_eq!(format!("{:o}", BigInt::one() << 100usize), format!("2{}", "0".repeat(33)));
+    assert_eq!(format!("{:.2e}", BigInt::from(10u8).pow(678) * 12345u32), "1.23e682");
+    assert_eq!(format!("{:e}", BigInt::from(10u8).pow(678) * -12345i32), "-1.2345e682");
+    assert_eq!(format!("{:.1e}", BigInt::from(10u8).pow(50) - 1u8), "1.0e50");
+}
+
+#[test]
+fn to_str_radix_test() {
+    use std::str::FromStr;
+    assert_eq!(BigInt::zero().to_str_radix(7), "0");
+    assert_eq!(BigInt::from(-1295i16).to_str_radix(36), "-zz");
+    assert_eq!(BigInt::from(255u8).to_str_radix(2), "11111111");
+    assert_eq!(BigInt::from(255u8).to_str_radix(32), "7v");
+
+    let s = "123456789abcdefghijklmnopqrstuvwxyz0".repeat(40);
+    for radix in 2..37 {
+        let digits: String = s.chars().filter(|c| c.is_digit(radix)).collect();
+        let x = BigInt::from_str_radix(&digits, radix).unwrap();
+        assert_eq!(x.to_str_radix(radix), digits.trim_start_matches('0'));
+        assert_eq!(x.convert_limbs::<u16>().to_str_radix(radix), x.to_str_radix(radix));
+        assert_eq!((-x.clone()).to_str_radix(radix), format!("-{}", x.to_str_radix(radix)));
+    }
+    assert_eq!(BigInt::from_str("-0x_ff").unwrap().to_str_radix(16), "-ff");
+}
+
+#[test]
+#[should_panic]
+fn to_str_radix_panic_test() {
+    BigInt::one().to_str_radix(37);
+}
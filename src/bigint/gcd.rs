@@ -0,0 +1,204 @@
+use bigint::{BigInt, Limb};
+use bigint::ops::scalar::small_magnitude;
+
+use std::mem;
+
+impl<L: Limb> BigInt<L> {
+    /// Returns the greatest common divisor of self and `other`. The result is never negative,
+    /// and is zero only if both arguments are zero.
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut u, mut v) = (self.abs(), other.abs());
+        if u < v {
+            mem::swap(&mut u, &mut v);
+        }
+
+        while !v.is_zero() {
+            if let (Some(a), Some(b)) = (small_magnitude(&u), small_magnitude(&v)) {
+                return BigInt::from_primitive(binary_gcd(a, b));
+            }
+            lehmer_step(&mut u, &mut v, None);
+        }
+        u
+    }
+
+    /// Returns the least common multiple of self and `other`. The result is never negative,
+    /// and is zero if either argument is zero.
+    pub fn lcm(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return BigInt::default();
+        }
+        (self / self.gcd(other) * other).abs()
+    }
+
+    /// Returns `(g, x, y)` where `g` is `self.gcd(other)` and `self * x + other * y == g`. The
+    /// coefficients are the ones found by the Euclidean algorithm, so `|x| <= |other| / g` and
+    /// `|y| <= |self| / g`.
+    pub fn extended_gcd(&self, other: &Self) -> (Self, Self, Self) {
+        let swap = self.abs() < other.abs();
+        let (a, b) = if swap { (other, self) } else { (self, other) };
+
+        let (mut u, mut v) = (a.abs(), b.abs());
+        let mut s = (BigInt::from_primitive(1u8), BigInt::default());
+        while !v.is_zero() {
+            lehmer_step(&mut u, &mut v, Some(&mut s));
+        }
+
+        // u = s * |a| + t * |b|, and t is found by dividing out the rest.
+        let mut x = if u.is_zero() { BigInt::default() } else { s.0 };
+        let mut y = if b.is_zero() {
+            BigInt::default()
+        } else {
+            (&u - &x * a.abs()) / b.abs()
+        };
+        if a.is_negative() {
+            x.negate();
+        }
+        if b.is_negative() {
+            y.negate();
+        }
+
+        if swap {
+            (u, y, x)
+        } else {
+            (u, x, y)
+        }
+    }
+
+    /// Returns the inverse of self modulo `m` in the range `0..|m|`, or `None` if self and `m`
+    /// share a factor or `m` is zero.
+    pub fn mod_inverse(&self, m: &Self) -> Option<Self> {
+        if m.is_zero() {
+            return None;
+        }
+        let m = m.abs();
+        let (g, x, _) = self.rem_euclid(&m).extended_gcd(&m);
+        if g == BigInt::from_primitive(1u8) {
+            Some(x.rem_euclid(&m))
+        } else {
+            None
+        }
+    }
+}
+
+/// Stein's binary GCD algorithm.
+fn binary_gcd(mut a: u128, mut b: u128) -> u128 {
+    if a == 0 || b == 0 {
+        return a | b;
+    }
+    let shift = (a | b).trailing_zeros();
+    a >>= a.trailing_zeros();
+    while b != 0 {
+        b >>= b.trailing_zeros();
+        if a > b {
+            mem::swap(&mut a, &mut b);
+        }
+        b -= a;
+    }
+    a << shift
+}
+
+/// Performs one step of Lehmer's algorithm (TAOCP 4.5.2 Algorithm L) on `u >= v > 0`. The
+/// Euclidean algorithm is simulated on the leading 64 bits of both numbers for as long as the
+/// quotients are certain, and the resulting matrix is applied to the full numbers in one go. If
+/// no quotient could be determined an ordinary division step is done instead.
+///
+/// If `cofactors` is given it is updated alongside `(u, v)`, so that a linear relation between
+/// the original inputs and `(u, v)` is kept.
+fn lehmer_step<L: Limb>(
+    u: &mut BigInt<L>,
+    v: &mut BigInt<L>,
+    cofactors: Option<&mut (BigInt<L>, BigInt<L>)>,
+) {
+    let shift = u.bits().saturating_sub(64);
+    let mut x = u.extract_bits_u64(shift..shift + 64) as i128;
+    let mut y = v.extract_bits_u64(shift..shift + 64) as i128;
+    let (mut a, mut b, mut c, mut d) = (1i128, 0i128, 0i128, 1i128);
+
+    while y + c != 0 && y + d != 0 {
+        let q = (x + a) / (y + c);
+        if q != (x + b) / (y + d) {
+            break;
+        }
+        let t = a - q * c;
+        a = c;
+        c = t;
+        let t = b - q * d;
+        b = d;
+        d = t;
+        let t = x - q * y;
+        x = y;
+        y = t;
+    }
+
+    if b == 0 {
+        let (q, r) = u.div_mod(v);
+        *u = mem::replace(v, r);
+        if let Some(&mut (ref mut s0, ref mut s1)) = cofactors {
+            let next = &*s0 - q * &*s1;
+            *s0 = mem::replace(s1, next);
+        }
+    } else {
+        let next = (&*u * c + &*v * d, &*u * a + &*v * b);
+        *v = next.0;
+        *u = next.1;
+        if let Some(&mut (ref mut s0, ref mut s1)) = cofactors {
+            let next = (&*s0 * c + &*s1 * d, &*s0 * a + &*s1 * b);
+            *s1 = next.0;
+            *s0 = next.1;
+        }
+    }
+}
+
+
+#[test]
+fn binary_gcd_test() {
+    assert_eq!(binary_gcd(0, 0), 0);
+    assert_eq!(binary_gcd(0, 12), 12);
+    assert_eq!(binary_gcd(48, 180), 12);
+    assert_eq!(binary_gcd(1 << 100, 3 << 90), 1 << 90);
+}
+
+#[test]
+fn gcd_test() {
+    let values = [-360i64, -17, -1, 0, 1, 6, 84, 97, 360, 1 << 40];
+    for &a in values.iter() {
+        for &b in values.iter() {
+            let (a, b) = (BigInt::from(a), BigInt::from(b));
+            let (g, x, y) = a.extended_gcd(&b);
+            assert_eq!(g, a.gcd(&b));
+            assert_eq!(&a * &x + &b * &y, g);
+            assert_eq!(&g * a.lcm(&b), (&a * &b).gcd(&BigInt::zero()));
+        }
+    }
+    assert_eq!(BigInt::from(-360).gcd(&BigInt::from(84)), BigInt::from(12));
+    assert_eq!(BigInt::from(-360).lcm(&BigInt::from(84)), BigInt::from(2520));
+}
+
+#[test]
+fn lehmer_gcd_test() {
+    // Consecutive Fibonacci numbers are coprime and are the worst case for Euclid.
+    let (mut a, mut b) = (BigInt::one(), BigInt::one());
+    for _ in 0..3000 {
+        let c = &a + &b;
+        a = b;
+        b = c;
+    }
+    let factor = BigInt::from(1000000007u32).pow(20);
+    let (g, x, y) = (&a * &factor).extended_gcd(&(&b * &factor));
+    assert_eq!(g, factor);
+    assert_eq!(&a * &factor * &x + &b * &factor * &y, factor);
+    assert!(x.bits() <= b.bits() && y.bits() <= a.bits());
+    assert_eq!((&a * &factor).gcd(&(&b * &factor)), factor);
+}
+
+#[test]
+fn mod_inverse_test() {
+    let m = BigInt::from(1000000007u32);
+    let a = BigInt::from(123456789u32);
+    let inv = a.mod_inverse(&m).unwrap();
+    assert_eq!((&a * &inv) % &m, BigInt::one());
+    assert_eq!((-a.clone()).mod_inverse(&-m.clone()), Some(&m - &inv));
+    assert_eq!(BigInt::from(6u8).mod_inverse(&BigInt::from(9u8)), None);
+    assert_eq!(a.mod_inverse(&BigInt::zero()), None);
+    assert_eq!(a.mod_inverse(&BigInt::one()), Some(BigInt::zero()));
+}
@@ -0,0 +1,226 @@
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+use bigint::digit::Limb;
+
+/// How many limbs are kept inside a LimbVec before it moves to the heap.
+const INLINE: usize = 2;
+
+/// Little-endian limb storage with the subset of the `Vec` interface that BigInt needs. Up to
+/// `INLINE` limbs are stored in place, so small values never allocate. Once a value outgrows
+/// that it moves to the heap and stays there, keeping its buffer for reuse.
+#[derive(Clone)]
+pub(crate) enum LimbVec<L: Limb> {
+    Inline(u8, [L; INLINE]),
+    Heap(Vec<L>),
+}
+
+impl<L: Limb> LimbVec<L> {
+    #[inline]
+    pub fn new() -> Self { LimbVec::Inline(0, [L::ZERO; INLINE]) }
+
+    /// Returns `len` zero limbs.
+    #[inline]
+    pub fn zeroed(len: usize) -> Self {
+        if len <= INLINE {
+            LimbVec::Inline(len as u8, [L::ZERO; INLINE])
+        } else {
+            LimbVec::Heap(vec![L::ZERO; len])
+        }
+    }
+
+    #[inline]
+    pub fn from_slice(src: &[L]) -> Self {
+        let mut out = LimbVec::new();
+        out.extend_from_slice(src);
+        out
+    }
+
+    pub fn into_vec(self) -> Vec<L> {
+        match self {
+            LimbVec::Inline(len, buf) => buf[..len as usize].to_vec(),
+            LimbVec::Heap(v) => v,
+        }
+    }
+
+    /// Returns the heap buffer, moving the limbs there first with room for at least `extra`
+    /// more if they are stored inline.
+    fn spill(&mut self, extra: usize) -> &mut Vec<L> {
+        if let LimbVec::Inline(len, buf) = *self {
+            let mut v = Vec::with_capacity((len as usize + extra).max(2 * INLINE));
+            v.extend_from_slice(&buf[..len as usize]);
+            *self = LimbVec::Heap(v);
+        }
+        match *self {
+            LimbVec::Heap(ref mut v) => v,
+            LimbVec::Inline(..) => unreachable!(),
+        }
+    }
+
+    #[inline]
+    pub fn push(&mut self, x: L) {
+        match *self {
+            LimbVec::Inline(ref mut len, ref mut buf) if (*len as usize) < INLINE => {
+                buf[*len as usize] = x;
+                *len += 1;
+            }
+            _ => self.spill(1).push(x),
+        }
+    }
+
+    #[inline]
+    pub fn pop(&mut self) -> Option<L> {
+        match *self {
+            LimbVec::Inline(0, _) => None,
+            LimbVec::Inline(ref mut len, buf) => {
+                *len -= 1;
+                Some(buf[*len as usize])
+            }
+            LimbVec::Heap(ref mut v) => v.pop(),
+        }
+    }
+
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        match *self {
+            LimbVec::Inline(ref mut len, _) => {
+                if new_len < *len as usize {
+                    *len = new_len as u8;
+                }
+            }
+            LimbVec::Heap(ref mut v) => v.truncate(new_len),
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) { self.truncate(0) }
+
+    pub fn resize(&mut self, new_len: usize, value: L) {
+        match *self {
+            LimbVec::Inline(ref mut len, ref mut buf) if new_len <= INLINE => {
+                for d in buf.iter_mut().take(new_len).skip(*len as usize) {
+                    *d = value;
+                }
+                *len = new_len as u8;
+            }
+            _ => {
+                let extra = new_len.saturating_sub(self.len());
+                self.spill(extra).resize(new_len, value)
+            }
+        }
+    }
+
+    pub fn extend_from_slice(&mut self, other: &[L]) {
+        match *self {
+            LimbVec::Inline(ref mut len, ref mut buf) if *len as usize + other.len() <= INLINE => {
+                let start = *len as usize;
+                buf[start..start + other.len()].copy_from_slice(other);
+                *len += other.len() as u8;
+            }
+            _ => self.spill(other.len()).extend_from_slice(other),
+        }
+    }
+}
+
+impl<L: Limb> Default for LimbVec<L> {
+    #[inline]
+    fn default() -> Self { LimbVec::new() }
+}
+
+impl<L: Limb> Deref for LimbVec<L> {
+    type Target = [L];
+    #[inline]
+    fn deref(&self) -> &[L] {
+        match *self {
+            LimbVec::Inline(len, ref buf) => &buf[..len as usize],
+            LimbVec::Heap(ref v) => v,
+        }
+    }
+}
+
+impl<L: Limb> DerefMut for LimbVec<L> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [L] {
+        match *self {
+            LimbVec::Inline(len, ref mut buf) => &mut buf[..len as usize],
+            LimbVec::Heap(ref mut v) => v,
+        }
+    }
+}
+
+impl<L: Limb> From<Vec<L>> for LimbVec<L> {
+    /// Takes over the buffer of `v` without copying.
+    #[inline]
+    fn from(v: Vec<L>) -> Self { LimbVec::Heap(v) }
+}
+
+impl<L: Limb> Extend<L> for LimbVec<L> {
+    fn extend<I: IntoIterator<Item = L>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<L: Limb> FromIterator<L> for LimbVec<L> {
+    fn from_iter<I: IntoIterator<Item = L>>(iter: I) -> Self {
+        let mut out = LimbVec::new();
+        out.extend(iter);
+        out
+    }
+}
+
+// Equality and hashing only look at the limbs, not at where they are stored.
+impl<L: Limb> PartialEq for LimbVec<L> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { **self == **other }
+}
+
+impl<L: Limb> Eq for LimbVec<L> {}
+
+impl<L: Limb> Hash for LimbVec<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) { (**self).hash(state) }
+}
+
+impl<L: Limb> Debug for LimbVec<L> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result { (**self).fmt(f) }
+}
+
+
+#[test]
+fn limb_vec_test() {
+    let mut v: LimbVec<u32> = LimbVec::new();
+    v.push(1);
+    v.push(2);
+    if let LimbVec::Heap(_) = v {
+        panic!("Two limbs should be stored inline");
+    }
+    v.push(3);
+    assert_eq!(&*v, &[1, 2, 3]);
+    assert_eq!(v.pop(), Some(3));
+    v.truncate(1);
+    assert_eq!(v, LimbVec::from_slice(&[1]));
+
+    let mut inline: LimbVec<u32> = LimbVec::from_slice(&[1, 2]);
+    inline.truncate(256);
+    assert_eq!(&*inline, &[1, 2]);
+
+    let mut w: LimbVec<u32> = LimbVec::from_slice(&[7]);
+    w.resize(2, 9);
+    assert_eq!(&*w, &[7, 9]);
+    w.extend_from_slice(&[4, 5]);
+    assert_eq!(w.clone().into_vec(), vec![7, 9, 4, 5]);
+    w.clear();
+    assert_eq!(w.pop(), None);
+    assert_eq!(LimbVec::<u64>::zeroed(5).len(), 5);
+    assert_eq!((0..3u16).collect::<LimbVec<u16>>().into_vec(), vec![0, 1, 2]);
+
+    // Values up to 64 bits never touch the heap.
+    use bigint::BigInt;
+    let small = (BigInt::from(3u8) * 5u8 + 1u8) << 40usize;
+    if let LimbVec::Heap(_) = small.digits {
+        panic!("Small values should be stored inline");
+    }
+}
@@ -3,30 +3,68 @@
 #![allow(dead_code)]
 
 mod ops;
+mod bits;
+mod pow;
+mod roots;
+mod primes;
+mod gcd;
+mod factorial;
+mod bytes;
 mod convert;
 mod digit;
+mod limbs;
 mod errors;
 mod sign;
 mod parse;
 mod format;
 
 
-use self::digit::{BigDigit, DoubleBigDigit};
-use self::errors::BigIntParseError;
-use self::sign::Sign;
+use self::limbs::LimbVec;
+use self::ops::scalar::Primitive;
+
+pub use self::bytes::Order;
+pub use self::digit::{BigDigit, DefaultLimb, Limb};
+pub use self::errors::{BigIntParseError, TryFromBigIntError, TryFromFloatError};
+pub use self::ops::div::RoundingMode;
+pub use self::sign::Sign;
 
 use std::ops::Neg;
 use std::cmp::{Ord, Ordering, PartialOrd};
 
-/// An arbitrary size integer
+/// An arbitrary size integer, stored as a sign and a magnitude made of limbs of type `L`.
+///
+/// Constructors that take nothing to infer `L` from, such as `zero`, `one`, `From` and
+/// `FromStr`, only exist for the default limb type so that they need no annotations. A BigInt
+/// with other limbs can be made with `Default`, `from_sign_limbs` or `convert_limbs`.
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
-pub struct BigInt {
+pub struct BigInt<L: Limb = DefaultLimb> {
     sign: Sign,
-    digits: Vec<BigDigit>,
+    digits: LimbVec<L>,
 }
 
 
 impl BigInt {
+    /// Returns a BigInt with a value of zero.
+    #[inline]
+    pub fn zero() -> Self { BigInt::default() }
+
+    /// Returns a BigInt with a value of positive 1.
+    #[inline]
+    pub fn one() -> Self { BigInt::from_primitive(1u8) }
+}
+
+impl<L: Limb> Default for BigInt<L> {
+    /// Returns zero.
+    #[inline]
+    fn default() -> Self {
+        BigInt {
+            sign: Sign::Zero,
+            digits: LimbVec::new(),
+        }
+    }
+}
+
+impl<L: Limb> BigInt<L> {
     /// Returns true if the BigInt is zero and false otherwise;
     #[inline]
     pub fn is_zero(&self) -> bool {
@@ -54,37 +92,172 @@ impl BigInt {
         }
     }
 
-    /// Returns a BigInt with a value of zero.
+    /// Changes self to have the opposite sign. No change if self is zero.
     #[inline]
-    pub fn zero() -> Self {
-        BigInt::from(0u8)
+    pub fn negate(&mut self) {
+        self.sign = -self.sign;
     }
 
-    /// Returns a BigInt with a value of positive 1.
+    /// Returns the sign of self.
     #[inline]
-    pub fn one() -> Self {
-        BigInt {
-            sign: Sign::Positive,
-            digits: vec![1],
+    pub fn sign(&self) -> Sign {
+        self.sign
+    }
+
+    /// Returns -1, 0 or 1 according to the sign of self.
+    pub fn signum(&self) -> Self {
+        match self.sign {
+            Sign::Positive => BigInt::from_primitive(1u8),
+            Sign::Negative => BigInt::from_primitive(-1i8),
+            Sign::Zero => BigInt::default(),
         }
     }
 
-    /// Changes self to have the opposite sign. No change if self is zero.
+    /// Returns the absolute value of self.
+    pub fn abs(&self) -> Self {
+        let mut out = self.clone();
+        if out.is_negative() {
+            out.negate();
+        }
+        out
+    }
+
+    /// Returns `|self - other|`.
+    pub fn abs_diff(&self, other: &Self) -> Self {
+        if self >= other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+
+    /// Creates a BigInt from a sign and little-endian limbs, taking ownership of the limbs
+    /// without copying them. Leading zero limbs are removed, and the result is zero if `sign` is
+    /// `Sign::Zero` or every limb is zero.
+    pub fn from_sign_limbs(sign: Sign, limbs: Vec<L>) -> Self {
+        if sign == Sign::Zero {
+            return BigInt::default();
+        }
+        let out = BigInt {
+            sign,
+            digits: limbs.into(),
+        };
+        out.trimmed()
+    }
+
+    /// Splits self into its sign and little-endian limbs, without copying the limbs. The limbs
+    /// have no leading zeroes, and are empty exactly when the sign is `Sign::Zero`.
+    pub fn into_parts(self) -> (Sign, Vec<L>) {
+        (self.sign, self.digits.into_vec())
+    }
+
+    /// Returns the little-endian limbs of the magnitude of self.
     #[inline]
-    pub fn negate(&mut self) {
-        self.sign = -self.sign;
+    pub fn as_limbs(&self) -> &[L] {
+        &self.digits
     }
 
+    /// Returns the magnitude of self as little-endian 64 bit words, whatever the width of
+    /// `L`. The last word is nonzero unless self is zero, in which case this is empty.
+    pub fn limbs_u64(&self) -> Vec<u64> {
+        let per_word = 64 / L::BITS;
+        let mut words: Vec<u64> = self
+            .digits
+            .chunks(per_word)
+            .map(|chunk| ops::scalar::to_u128(chunk) as u64)
+            .collect();
+        while let Some(&0) = words.last() {
+            words.pop();
+        }
+        words
+    }
+
+    /// Returns the same value stored in limbs of type `M`.
+    pub fn convert_limbs<M: Limb>(&self) -> BigInt<M> {
+        let mut digits = Vec::with_capacity(self.bits().div_ceil(M::BITS) + 1);
+        let (mut acc, mut acc_bits) = (0u128, 0);
+        for &d in self.digits.iter() {
+            acc |= d.to_u128() << acc_bits;
+            acc_bits += L::BITS;
+            while acc_bits >= M::BITS {
+                digits.push(M::truncate(acc));
+                acc >>= M::BITS;
+                acc_bits -= M::BITS;
+            }
+        }
+        digits.push(M::truncate(acc));
+        BigInt::from_sign_limbs(self.sign, digits)
+    }
+
+    /// Returns a BigInt equal to the primitive integer `x`.
+    #[inline]
+    pub(crate) fn from_primitive<T: Primitive>(x: T) -> Self { x.to_scalar().into_bigint() }
+
     /// Returns a tuple of the quotient and remainder of self divided by the
-    /// argument.
-    pub fn div_mod(&self, rhs: &BigInt) -> (BigInt, BigInt) {
-        let (quo, rem) = ops::div::divmod(self.clone(), rhs.clone(), true);
+    /// argument. The quotient is truncated toward zero and the remainder has
+    /// the same sign as self, matching the primitive integer types.
+    pub fn div_mod(&self, rhs: &Self) -> (Self, Self) {
+        let (quo, rem) = ops::div::truncated_divmod(self.clone(), rhs.clone(), true);
         (quo, rem.unwrap())
     }
 
+    /// Returns `self / rhs` rounded toward negative infinity.
+    pub fn div_floor(&self, rhs: &Self) -> Self {
+        ops::div::floor_divmod(self.clone(), rhs.clone()).0
+    }
+
+    /// Returns the remainder of `div_floor`, which has the same sign as `rhs`.
+    pub fn mod_floor(&self, rhs: &Self) -> Self {
+        ops::div::floor_divmod(self.clone(), rhs.clone()).1
+    }
+
+    /// Returns `(self.div_floor(rhs), self.mod_floor(rhs))`.
+    pub fn div_mod_floor(&self, rhs: &Self) -> (Self, Self) {
+        ops::div::floor_divmod(self.clone(), rhs.clone())
+    }
+
+    /// Returns `self / rhs` rounded toward positive infinity.
+    pub fn div_ceil(&self, rhs: &Self) -> Self {
+        ops::div::ceil_divmod(self.clone(), rhs.clone()).0
+    }
+
+    /// Returns the quotient of Euclidean division, chosen so that
+    /// `self.rem_euclid(rhs)` is never negative.
+    pub fn div_euclid(&self, rhs: &Self) -> Self {
+        ops::div::euclid_divmod(self.clone(), rhs.clone()).0
+    }
+
+    /// Returns the least nonnegative remainder of `self` modulo `rhs`.
+    pub fn rem_euclid(&self, rhs: &Self) -> Self {
+        ops::div::euclid_divmod(self.clone(), rhs.clone()).1
+    }
+
+    /// Returns `self / rhs` rounded to an integer according to `mode`.
+    pub fn div_round(&self, rhs: &Self, mode: RoundingMode) -> Self {
+        ops::div::round_div(self.clone(), rhs.clone(), mode)
+    }
+
+    /// Returns `self / rhs`, or `None` if `rhs` is zero.
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        if rhs.is_zero() {
+            None
+        } else {
+            Some(self / rhs)
+        }
+    }
+
+    /// Returns `self % rhs`, or `None` if `rhs` is zero.
+    pub fn checked_rem(&self, rhs: &Self) -> Option<Self> {
+        if rhs.is_zero() {
+            None
+        } else {
+            Some(self % rhs)
+        }
+    }
+
     #[inline]
     fn trim(&mut self) {
-        while let Some(&0) = self.digits.last() {
+        while self.digits.last() == Some(&L::ZERO) {
             self.digits.pop();
         }
 
@@ -105,11 +278,11 @@ impl BigInt {
     #[inline]
     fn grow_to_hold(&mut self, size: usize) {
         if size > self.digits.len() {
-            self.digits.resize(size, 0);
+            self.digits.resize(size, L::ZERO);
         }
     }
 
-    fn from_vec(src: Vec<BigDigit>) -> Self {
+    fn from_vec(src: LimbVec<L>) -> Self {
         let out = BigInt {
             sign: Sign::Positive,
             digits: src,
@@ -118,7 +291,7 @@ impl BigInt {
     }
 }
 
-impl Neg for BigInt {
+impl<L: Limb> Neg for BigInt<L> {
     type Output = Self;
     #[inline]
     fn neg(mut self) -> Self::Output {
@@ -127,7 +300,7 @@ impl Neg for BigInt {
     }
 }
 
-impl PartialOrd for BigInt {
+impl<L: Limb> PartialOrd for BigInt<L> {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -135,7 +308,7 @@ impl PartialOrd for BigInt {
 }
 
 
-impl Ord for BigInt {
+impl<L: Limb> Ord for BigInt<L> {
     fn cmp(&self, other: &Self) -> Ordering {
         use self::Ordering::*;
         use self::Sign::*;
@@ -181,38 +354,55 @@ impl Ord for BigInt {
 #[test]
 fn trim_test() {
     use bigint::sign::Sign;
-    let mut z = BigInt {
+    let mut z: BigInt = BigInt {
         sign: Sign::Positive,
-        digits: vec![0; 50],
+        digits: LimbVec::zeroed(50),
     };
     z.trim();
     assert!(z.is_zero());
 }
 
-#[cfg(all(target_pointer_width = "64", not(feature = "thicc_ints")))]
 #[test]
 fn from_str_radix_test_1() {
-    use bigint::sign::Sign;
     use std::str::FromStr;
-    let s = "22209053970854587616243584284722270";
-    let a = BigInt {
-        sign: Sign::Positive,
-        digits: vec![779322462, 594349670, 2880689586, 280317],
-    };
-    let b = BigInt::from_str(s).unwrap();
-    assert_eq!(a, b);
+    let b = BigInt::from_str("22209053970854587616243584284722270").unwrap();
+    assert_eq!(b.sign(), Sign::Positive);
+    assert_eq!(b.limbs_u64(), vec![0x236d_0e66_2e73_845e, 0x4_46fd_abb3_d5b2]);
 }
 
-#[cfg(all(target_pointer_width = "64", not(feature = "thicc_ints")))]
 #[test]
 fn from_str_radix_test_2() {
-    use bigint::sign::Sign;
     use std::str::FromStr;
-    let s = "-22209053970854587616243584284722270";
-    let a = BigInt {
-        sign: Sign::Negative,
-        digits: vec![779322462, 594349670, 2880689586, 280317],
-    };
-    let b = BigInt::from_str(s).unwrap();
-    assert_eq!(a, b);
+    let b = BigInt::from_str("-22209053970854587616243584284722270").unwrap();
+    assert_eq!(b.sign(), Sign::Negative);
+    assert_eq!(b.limbs_u64(), vec![0x236d_0e66_2e73_845e, 0x4_46fd_abb3_d5b2]);
+}
+
+#[test]
+fn sign_helpers_test() {
+    let a = -BigInt::from(7u8);
+    let b = BigInt::from(12u8);
+    assert_eq!(a.sign(), Sign::Negative);
+    assert_eq!(a.signum(), -BigInt::one());
+    assert_eq!(BigInt::zero().signum(), BigInt::zero());
+    assert_eq!(b.signum(), BigInt::one());
+    assert_eq!(a.abs(), BigInt::from(7u8));
+    assert_eq!(a.abs_diff(&b), BigInt::from(19u8));
+    assert_eq!(b.abs_diff(&a), BigInt::from(19u8));
+}
+
+#[test]
+fn limb_parts_test() {
+    let x = -(BigInt::from(0x0123_4567_89ab_cdefu64) << 64usize) - 5u8;
+    assert_eq!(x.limbs_u64(), vec![5, 0x0123_4567_89ab_cdef]);
+
+    let (sign, limbs) = x.clone().into_parts();
+    assert_eq!(sign, Sign::Negative);
+    assert_eq!(limbs, x.as_limbs());
+    assert_eq!(BigInt::from_sign_limbs(sign, limbs), x);
+
+    assert_eq!(BigInt::from_sign_limbs(Sign::Positive, vec![0, 0]), BigInt::zero());
+    assert_eq!(BigInt::from_sign_limbs(Sign::Zero, vec![1]), BigInt::zero());
+    assert_eq!(BigInt::zero().into_parts(), (Sign::Zero, vec![]));
+    assert!(BigInt::zero().limbs_u64().is_empty());
 }
@@ -1,105 +1,147 @@
-use bigint::{BigInt, BigDigit, DoubleBigDigit};
+use bigint::BigInt;
 use bigint::Sign::*;
-use bigint::digit::{to_lo_hi};
+use bigint::digit::Limb;
+use bigint::ops::sub::ssub;
+use bigint::sign::Sign;
 
-use std::ops::Add;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
 
 
 
-impl Add<BigInt> for BigInt {
-    type Output = BigInt;
-    fn add(mut self, rhs: BigInt) -> Self::Output {
-        match (self.sign, rhs.sign) {
-            (Positive, Positive) => {
-                self.grow_to_hold(rhs.digits.len());
-                let carry = sadd(&mut self.digits, &rhs.digits);
-                self.digits.push(carry as BigDigit);
-                self.trimmed()
-            }
-            (Positive, Negative) => self - (-rhs),
-            (Zero, _) => rhs,
-            (_, Zero) => self,
-            (Negative, Positive) => rhs - self,
-            (Negative, Negative) => -(-self + -rhs),
+impl<L: Limb> Add<BigInt<L>> for BigInt<L> {
+    type Output = BigInt<L>;
+    fn add(mut self, mut rhs: BigInt<L>) -> Self::Output {
+        // Reuse whichever buffer is already large enough to hold the sum.
+        if rhs.digits.len() > self.digits.len() {
+            rhs += &self;
+            rhs
+        } else {
+            self += &rhs;
+            self
         }
     }
 }
 
 
-impl<'a, 'b> Add<&'b BigInt> for &'a BigInt {
-    type Output = BigInt;
-    fn add(self, rhs: &'b BigInt) -> Self::Output {
-        if self >= rhs {
+impl<'a, 'b, L: Limb> Add<&'b BigInt<L>> for &'a BigInt<L> {
+    type Output = BigInt<L>;
+    fn add(self, rhs: &'b BigInt<L>) -> Self::Output {
+        if self.digits.len() >= rhs.digits.len() {
             let mut output = self.clone();
-            add_and_grow(&mut output, rhs);
+            output += rhs;
             output
         } else {
             let mut output = rhs.clone();
-            add_and_grow(&mut output, self);
+            output += self;
             output
         }
     }
 }
 
-impl<'a> Add<&'a BigInt> for BigInt {
-    type Output = BigInt;
-    fn add(mut self, rhs: &'a BigInt) -> Self::Output {
-        add_and_grow(&mut self, rhs);
+impl<'a, L: Limb> Add<&'a BigInt<L>> for BigInt<L> {
+    type Output = BigInt<L>;
+    fn add(mut self, rhs: &'a BigInt<L>) -> Self::Output {
+        self += rhs;
         self
     }
 }
 
-impl Add<BigDigit> for BigInt {
-    type Output = BigInt;
-    fn add(mut self, rhs: BigDigit) -> Self::Output {
-        if self.is_zero() {
-            self.digits.push(rhs);
-            return self;
-        }
-        let carry = sadd_digit(&mut self.digits, rhs);
-        if carry > 0 {
-            self.digits.push(carry);
-        }
-        self
+impl<'a, L: Limb> Add<BigInt<L>> for &'a BigInt<L> {
+    type Output = BigInt<L>;
+    fn add(self, mut rhs: BigInt<L>) -> Self::Output {
+        rhs += self;
+        rhs
+    }
+}
+
+impl<L: Limb> AddAssign<BigInt<L>> for BigInt<L> {
+    #[inline]
+    fn add_assign(&mut self, rhs: BigInt<L>) {
+        *self += &rhs;
+    }
+}
+
+impl<'a, L: Limb> AddAssign<&'a BigInt<L>> for BigInt<L> {
+    #[inline]
+    fn add_assign(&mut self, rhs: &'a BigInt<L>) {
+        add_assign_slice(self, rhs.sign, &rhs.digits);
+    }
+}
+
+impl<L: Limb> Sum<BigInt<L>> for BigInt<L> {
+    fn sum<I: Iterator<Item = BigInt<L>>>(iter: I) -> Self {
+        iter.fold(BigInt::default(), |mut acc, x| {
+            acc += x;
+            acc
+        })
+    }
+}
+
+impl<'a, L: Limb> Sum<&'a BigInt<L>> for BigInt<L> {
+    fn sum<I: Iterator<Item = &'a BigInt<L>>>(iter: I) -> Self {
+        iter.fold(BigInt::default(), |mut acc, x| {
+            acc += x;
+            acc
+        })
     }
 }
 
-pub(crate) fn add_and_grow(lhs: &mut BigInt, rhs: &BigInt) {
-    lhs.grow_to_hold(rhs.digits.len());
-    let grow = sadd(&mut lhs.digits, &rhs.digits);
-    if grow > 0 {
-        lhs.digits.push(grow);
+/// Adds the number with sign `rhs_sign` and magnitude `rhs` to `lhs`, reusing the digit buffer
+/// of `lhs`.
+pub(crate) fn add_assign_slice<L: Limb>(lhs: &mut BigInt<L>, rhs_sign: Sign, rhs: &[L]) {
+    match (lhs.sign, rhs_sign) {
+        (_, Zero) => (),
+        (Zero, _) => {
+            lhs.digits.clear();
+            lhs.digits.extend_from_slice(rhs);
+            lhs.sign = rhs_sign;
+            lhs.trim();
+        }
+        (l, r) if l == r => {
+            lhs.grow_to_hold(rhs.len());
+            let carry = sadd(&mut lhs.digits, rhs);
+            if carry != L::ZERO {
+                lhs.digits.push(carry);
+            }
+        }
+        _ => {
+            lhs.grow_to_hold(rhs.len());
+            if let Negative = ssub(&mut lhs.digits, rhs) {
+                lhs.sign = -lhs.sign;
+            }
+            lhs.trim();
+        }
     }
-    lhs.trim()
 }
 
 // TODO: Optimize
-pub(crate) fn sadd(lhs: &mut [BigDigit], rhs: &[BigDigit]) -> BigDigit {
+pub(crate) fn sadd<L: Limb>(lhs: &mut [L], rhs: &[L]) -> L {
     debug_assert!(lhs.len() >= rhs.len());
-    let mut carry: BigDigit = 0;
+    let mut carry = L::ZERO;
     let (l_lo, l_hi) = lhs.split_at_mut(rhs.len());
     for (l, r) in l_lo.iter_mut().zip(rhs.iter().cloned()) {
-        let [lo, hi] = to_lo_hi(*l as DoubleBigDigit + r as DoubleBigDigit + carry as DoubleBigDigit);
+        let [lo, hi] = L::to_lo_hi(l.widen() + r.widen() + carry.widen());
         *l = lo;
         carry = hi;
     }
 
-    if carry != 0 {
+    if carry != L::ZERO {
         carry = sadd_digit(l_hi, carry);
     }
     carry
 }
 
-pub(crate) fn sadd_digit(lhs: &mut [BigDigit], rhs: BigDigit) -> BigDigit {
+pub(crate) fn sadd_digit<L: Limb>(lhs: &mut [L], rhs: L) -> L {
     let mut carry = rhs;
 
     for ele in lhs.iter_mut() {
-        if carry == 0 {
+        if carry == L::ZERO {
             break;
         }
         let (res, c) = ele.overflowing_add(carry);
         *ele = res;
-        carry = c as BigDigit;
+        carry = if c { L::ONE } else { L::ZERO };
     }
     carry
 }
@@ -107,22 +149,38 @@ pub(crate) fn sadd_digit(lhs: &mut [BigDigit], rhs: BigDigit) -> BigDigit {
 
 #[test]
 fn add_test_1() {
-    use bigint::sign::Sign;
-
-    let a: BigDigit = 0;
-    let a = a.wrapping_sub(1);
-
-    let a_big = BigInt::from(a);
-    let b_big = BigInt::from(a);
-    let c_big = a_big + b_big;
+    fn check<L: Limb>() {
+        let a = BigInt::from_sign_limbs(Sign::Positive, vec![L::MAX]);
+        let c = &a + &a;
+        assert_eq!(c.as_limbs(), [L::MAX - L::ONE, L::ONE]);
+        assert_eq!(c - &a, a);
+    }
+    check::<u16>();
+    check::<u32>();
+    check::<u64>();
+}
 
-    let z: BigDigit = 0;
-    let z = z.wrapping_sub(2);
+#[test]
+fn add_assign_test() {
+    use bigint::digit::BigDigit;
+    let mut acc = BigInt::zero();
+    for i in 0..100u32 {
+        acc += BigInt::from(i);
+        acc += -BigInt::from(2 * i);
+        acc += &BigInt::from(i);
+        acc += i as BigDigit;
+    }
+    assert_eq!(acc, BigInt::from(4950u32));
 
-    let c_fixed = BigInt {
-        sign: Sign::Positive,
-        digits: vec![z, 1],
-    };
+    let mut x = -BigInt::from(5u8);
+    x += 7;
+    assert_eq!(x, BigInt::from(2u8));
+}
 
-    assert_eq!(c_big, c_fixed);
+#[test]
+fn sum_test() {
+    let values: Vec<BigInt> = (0..100u32).map(|i| BigInt::from(i) - 50u8).collect();
+    assert_eq!(values.iter().sum::<BigInt>(), -BigInt::from(50u8));
+    assert_eq!(values.into_iter().sum::<BigInt>(), -BigInt::from(50u8));
+    assert_eq!(Vec::<BigInt>::new().into_iter().sum::<BigInt>(), BigInt::zero());
 }
@@ -0,0 +1,198 @@
+use bigint::BigInt;
+use bigint::digit::Limb;
+use bigint::sign::Sign;
+
+use std::cmp::{max, min};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use std::slice;
+
+impl<L: Limb> Not for BigInt<L> {
+    type Output = BigInt<L>;
+    fn not(self) -> Self::Output { -(self + 1u8) }
+}
+
+impl<'a, L: Limb> Not for &'a BigInt<L> {
+    type Output = BigInt<L>;
+    fn not(self) -> Self::Output { -(self + 1u8) }
+}
+
+/// Implements a bitwise operator for every combination of owned and borrowed operands in terms
+/// of `bitop_assign`.
+macro_rules! impl_bitop {
+    ($tr:ident, $method:ident, $tr_assign:ident, $method_assign:ident, $op:expr) => {
+        impl<'a, L: Limb> $tr_assign<&'a BigInt<L>> for BigInt<L> {
+            #[inline]
+            fn $method_assign(&mut self, rhs: &'a BigInt<L>) { bitop_assign(self, rhs, $op); }
+        }
+
+        impl<L: Limb> $tr_assign<BigInt<L>> for BigInt<L> {
+            #[inline]
+            fn $method_assign(&mut self, rhs: BigInt<L>) { bitop_assign(self, &rhs, $op); }
+        }
+
+        impl<L: Limb> $tr<BigInt<L>> for BigInt<L> {
+            type Output = BigInt<L>;
+            fn $method(mut self, rhs: BigInt<L>) -> Self::Output {
+                bitop_assign(&mut self, &rhs, $op);
+                self
+            }
+        }
+
+        impl<'a, L: Limb> $tr<&'a BigInt<L>> for BigInt<L> {
+            type Output = BigInt<L>;
+            fn $method(mut self, rhs: &'a BigInt<L>) -> Self::Output {
+                bitop_assign(&mut self, rhs, $op);
+                self
+            }
+        }
+
+        impl<'a, L: Limb> $tr<BigInt<L>> for &'a BigInt<L> {
+            type Output = BigInt<L>;
+            fn $method(self, mut rhs: BigInt<L>) -> Self::Output {
+                // All three operations are commutative.
+                bitop_assign(&mut rhs, self, $op);
+                rhs
+            }
+        }
+
+        impl<'a, 'b, L: Limb> $tr<&'a BigInt<L>> for &'b BigInt<L> {
+            type Output = BigInt<L>;
+            fn $method(self, rhs: &'a BigInt<L>) -> Self::Output {
+                let mut out = self.clone();
+                bitop_assign(&mut out, rhs, $op);
+                out
+            }
+        }
+    }
+}
+
+impl_bitop!(BitAnd, bitand, BitAndAssign, bitand_assign, BitOp::And);
+impl_bitop!(BitOr, bitor, BitOrAssign, bitor_assign, BitOp::Or);
+impl_bitop!(BitXor, bitxor, BitXorAssign, bitxor_assign, BitOp::Xor);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BitOp {
+    And,
+    Or,
+    Xor,
+}
+
+impl BitOp {
+    #[inline]
+    fn apply<L: Limb>(self, a: L, b: L) -> L {
+        match self {
+            BitOp::And => a & b,
+            BitOp::Or => a | b,
+            BitOp::Xor => a ^ b,
+        }
+    }
+
+    /// Returns how many digits of two's complement output are needed before every further digit
+    /// is a copy of the sign. Past the end of its magnitude a nonnegative operand is all zeroes
+    /// and a negative one is all ones, so most cases only need the length of one operand.
+    fn output_len<L: Limb>(self, lhs: &BigInt<L>, rhs: &BigInt<L>) -> usize {
+        let (l_len, r_len) = (lhs.digits.len(), rhs.digits.len());
+        match (self, lhs.is_negative(), rhs.is_negative()) {
+            (BitOp::And, false, false) => min(l_len, r_len),
+            (BitOp::And, false, true) => l_len,
+            (BitOp::And, true, false) => r_len,
+            (BitOp::Or, true, true) => min(l_len, r_len),
+            (BitOp::Or, false, true) => r_len,
+            (BitOp::Or, true, false) => l_len,
+            (BitOp::Or, false, false) => max(l_len, r_len),
+            // The magnitude of a negative result can be one bit longer than either input.
+            _ => max(l_len, r_len) + 1,
+        }
+    }
+}
+
+/// Yields the digits of the infinite two's complement form of a number, computing them from
+/// the sign-magnitude digits as it goes.
+pub(crate) struct TwosComplement<'a, L: Limb> {
+    digits: slice::Iter<'a, L>,
+    negative: bool,
+    carry: bool,
+}
+
+impl<'a, L: Limb> TwosComplement<'a, L> {
+    pub fn new(input: &'a BigInt<L>) -> Self {
+        TwosComplement {
+            digits: input.digits.iter(),
+            negative: input.is_negative(),
+            carry: true,
+        }
+    }
+}
+
+impl<'a, L: Limb> Iterator for TwosComplement<'a, L> {
+    type Item = L;
+
+    #[inline]
+    fn next(&mut self) -> Option<L> {
+        let d = self.digits.next().cloned().unwrap_or(L::ZERO);
+        if self.negative {
+            Some(negate_digit(d, &mut self.carry))
+        } else {
+            Some(d)
+        }
+    }
+}
+
+/// One step of two's complement negation: returns `!d + carry` and updates the carry.
+#[inline]
+fn negate_digit<L: Limb>(d: L, carry: &mut bool) -> L {
+    let (res, c) = (!d).overflowing_add(if *carry { L::ONE } else { L::ZERO });
+    *carry = c;
+    res
+}
+
+/// Sets `lhs` to `lhs op rhs`, treating both as infinite two's complement numbers. The result is
+/// computed in the digit buffer of `lhs`.
+fn bitop_assign<L: Limb>(lhs: &mut BigInt<L>, rhs: &BigInt<L>, op: BitOp) {
+    let sign_digit = |x: &BigInt<L>| if x.is_negative() { L::MAX } else { L::ZERO };
+    let negative = op.apply(sign_digit(lhs), sign_digit(rhs)) != L::ZERO;
+    let len = op.output_len(lhs, rhs);
+    let lhs_negative = lhs.is_negative();
+
+    lhs.digits.resize(len, L::ZERO);
+
+    let mut lhs_carry = true;
+    let mut out_carry = true;
+    for (l, r) in lhs.digits.iter_mut().zip(TwosComplement::new(rhs)) {
+        let l_twos = if lhs_negative { negate_digit(*l, &mut lhs_carry) } else { *l };
+        let res = op.apply(l_twos, r);
+        // Convert a negative result back to its magnitude as we go.
+        *l = if negative { negate_digit(res, &mut out_carry) } else { res };
+    }
+
+    lhs.sign = if negative { Sign::Negative } else { Sign::Positive };
+    lhs.trim();
+}
+
+
+#[test]
+fn bitop_small_test() {
+    let values = [
+        ::std::i64::MIN, -(1i64 << 40) - 3, -(1 << 32), -65, -2, -1,
+        0, 1, 7, 64, 1 << 32, 1 << 40 | 5, ::std::i64::MAX,
+    ];
+    for &a in values.iter() {
+        assert_eq!(!BigInt::from(a), BigInt::from(!a));
+        for &b in values.iter() {
+            assert_eq!(BigInt::from(a) & BigInt::from(b), BigInt::from(a & b));
+            assert_eq!(BigInt::from(a) | BigInt::from(b), BigInt::from(a | b));
+            assert_eq!(BigInt::from(a) ^ BigInt::from(b), BigInt::from(a ^ b));
+        }
+    }
+}
+
+#[test]
+fn bitop_identity_test() {
+    use std::str::FromStr;
+    let x = BigInt::from_str("-98234092834092384092384902384029384").unwrap();
+
+    assert_eq!(-BigInt::one() & &x, x);
+    assert_eq!(&x | BigInt::zero(), x);
+    assert_eq!(&x ^ &x, BigInt::zero());
+    assert_eq!(!x.clone(), -x - BigInt::one());
+}
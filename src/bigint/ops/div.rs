@@ -1,134 +1,315 @@
 use bigint::BigInt;
-use bigint::digit::{BigDigit, DoubleBigDigit, from_lo_hi, to_lo_hi};
-use bigint::digit::constants::DIGIT_MAX;
+use bigint::digit::Limb;
+use bigint::limbs::LimbVec;
 use bigint::sign::Sign;
 
 use bigint::ops::add::sadd;
 use bigint::ops::sub::dsub;
 
 use std::cmp::Ordering::*;
-use std::ops::{ShlAssign, Div};
+use std::mem;
+use std::ops::{ShlAssign, Div, DivAssign};
 
-impl Div<BigDigit> for BigInt {
-    type Output = BigInt;
-    fn div(self, rhs: BigDigit) -> Self::Output {
-        short_divmod(&self, rhs, false).0
+impl<L: Limb> Div<BigInt<L>> for BigInt<L> {
+    type Output = BigInt<L>;
+    fn div(self, rhs: BigInt<L>) -> Self::Output { truncated_divmod(self, rhs, false).0 }
+}
+
+impl<'a, L: Limb> Div<&'a BigInt<L>> for BigInt<L> {
+    type Output = BigInt<L>;
+    fn div(self, rhs: &'a BigInt<L>) -> Self::Output {
+        truncated_divmod(self, rhs.clone(), false).0
     }
 }
 
-impl<'a> Div<BigDigit> for &'a BigInt {
-    type Output = BigInt;
-    fn div(self, rhs: BigDigit) -> Self::Output {
-        short_divmod(self, rhs, false).0
+impl<'a, L: Limb> Div<BigInt<L>> for &'a BigInt<L> {
+    type Output = BigInt<L>;
+    fn div(self, rhs: BigInt<L>) -> Self::Output { truncated_divmod(self.clone(), rhs, false).0 }
+}
+
+impl<'a, 'b, L: Limb> Div<&'a BigInt<L>> for &'b BigInt<L> {
+    type Output = BigInt<L>;
+    fn div(self, rhs: &'a BigInt<L>) -> Self::Output {
+        truncated_divmod(self.clone(), rhs.clone(), false).0
     }
 }
 
+impl<L: Limb> DivAssign<BigInt<L>> for BigInt<L> {
+    #[inline]
+    fn div_assign(&mut self, rhs: BigInt<L>) {
+        *self /= &rhs;
+    }
+}
 
+impl<'a, L: Limb> DivAssign<&'a BigInt<L>> for BigInt<L> {
+    #[inline]
+    fn div_assign(&mut self, rhs: &'a BigInt<L>) {
+        div_assign_slice(self, rhs.sign, &rhs.digits);
+    }
+}
 
-pub(crate) fn short_divmod(dividend: &BigInt, divisor: BigDigit, return_remainder: bool) -> (BigInt, Option<BigDigit>) {
-    assert!(divisor != 0, "Can't divide by zero");
+/// Divides `lhs` by the number with sign `rhs_sign` and magnitude `rhs`, truncating toward zero.
+/// Single digit divisors reuse the digit buffer of `lhs`.
+pub(crate) fn div_assign_slice<L: Limb>(lhs: &mut BigInt<L>, rhs_sign: Sign, rhs: &[L]) {
+    assert!(rhs_sign != Sign::Zero, "Can't divide by zero");
+    if rhs.len() == 1 {
+        ddiv(&mut lhs.digits, rhs[0]);
+        lhs.trim();
+    } else {
+        let divisor = BigInt {
+            sign: Sign::Positive,
+            digits: LimbVec::from_slice(rhs),
+        };
+        let dividend = mem::take(lhs);
+        *lhs = truncated_divmod(dividend, divisor, false).0;
+    }
+    lhs.sign = lhs.sign * rhs_sign;
+}
+
+pub(crate) fn short_divmod<L: Limb>(
+    dividend: &BigInt<L>,
+    divisor: L,
+    return_remainder: bool,
+) -> (BigInt<L>, Option<L>) {
+    assert!(divisor != L::ZERO, "Can't divide by zero");
     if dividend.is_zero() {
         if return_remainder {
-            return (BigInt::zero(), Some(0))
+            return (BigInt::default(), Some(L::ZERO))
         } else {
-            return (BigInt::zero(), None)
+            return (BigInt::default(), None)
         }
     }
 
-    let divisor = divisor as DoubleBigDigit;
-    let mut quo = vec![0; dividend.digits.len()];
-    let mut carry: BigDigit = 0;
+    let mut quo = dividend.clone();
+    let carry = ddiv(&mut quo.digits, divisor);
+    quo.trim();
 
-    for (d, q) in dividend.digits.iter().cloned().zip(quo.iter_mut()).rev() {
-        let res = (from_lo_hi([d, carry]) / divisor) as BigDigit;
-        let rem = (from_lo_hi([d, carry]) % divisor) as BigDigit;
-        *q = res;
-        carry = rem;
+    if return_remainder {
+        (quo, Some(carry))
+    } else {
+        (quo, None)
     }
+}
 
-    if return_remainder {
-        (BigInt::from_vec(quo), Some(carry))
+/// "Digit divide", divides lhs by rhs in-place and returns the remainder.
+pub(crate) fn ddiv<L: Limb>(lhs: &mut [L], rhs: L) -> L {
+    let rhs = rhs.widen();
+    let mut carry = L::ZERO;
+
+    for d in lhs.iter_mut().rev() {
+        let cur = L::from_lo_hi([*d, carry]);
+        *d = L::to_lo_hi(cur / rhs)[0];
+        carry = L::to_lo_hi(cur % rhs)[0];
+    }
+    carry
+}
+
+/// "Digit remainder", returns lhs modulo rhs without modifying lhs.
+pub(crate) fn drem<L: Limb>(lhs: &[L], rhs: L) -> L {
+    let rhs = rhs.widen();
+    let mut carry = L::ZERO;
+
+    for d in lhs.iter().cloned().rev() {
+        carry = L::to_lo_hi(L::from_lo_hi([d, carry]) % rhs)[0];
+    }
+    carry
+}
+
+/// Divides `dividend` by `divisor` with the semantics of Rust's primitive integers: the quotient
+/// is truncated toward zero and the remainder takes the sign of the dividend.
+pub(crate) fn truncated_divmod<L: Limb>(
+    mut dividend: BigInt<L>,
+    mut divisor: BigInt<L>,
+    return_remainder: bool,
+) -> (BigInt<L>, Option<BigInt<L>>) {
+    let (dividend_sign, divisor_sign) = (dividend.sign, divisor.sign);
+    if dividend.is_negative() {
+        dividend.negate();
+    }
+    if divisor.is_negative() {
+        divisor.negate();
+    }
+
+    let (mut quo, rem) = divmod(dividend, divisor, return_remainder);
+    quo.sign = quo.sign * dividend_sign * divisor_sign;
+    let rem = rem.map(|mut r| {
+        r.sign = r.sign * dividend_sign;
+        r
+    });
+    (quo, rem)
+}
+
+/// The rule used by `BigInt::div_round` to pick an integer quotient when the exact quotient is
+/// not an integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Round to the nearest integer, with ties going to the even neighbour (banker's rounding).
+    HalfEven,
+    /// Round to the nearest integer, with ties going away from zero.
+    HalfUp,
+    /// Round to the nearest integer, with ties going toward zero.
+    HalfDown,
+    /// Discard the fractional part.
+    TowardZero,
+    /// Round any inexact quotient away from zero.
+    AwayFromZero,
+}
+
+/// Returns the quotient rounded toward negative infinity, and a remainder with the sign of the
+/// divisor.
+pub(crate) fn floor_divmod<L: Limb>(
+    dividend: BigInt<L>,
+    divisor: BigInt<L>,
+) -> (BigInt<L>, BigInt<L>) {
+    let (quo, rem) = truncated_divmod(dividend, divisor.clone(), true);
+    let rem = rem.unwrap();
+    if !rem.is_zero() && rem.sign != divisor.sign {
+        (quo - 1u8, rem + divisor)
+    } else {
+        (quo, rem)
+    }
+}
+
+/// Returns the quotient rounded toward positive infinity, and a remainder with the opposite sign
+/// of the divisor.
+pub(crate) fn ceil_divmod<L: Limb>(
+    dividend: BigInt<L>,
+    divisor: BigInt<L>,
+) -> (BigInt<L>, BigInt<L>) {
+    let (quo, rem) = truncated_divmod(dividend, divisor.clone(), true);
+    let rem = rem.unwrap();
+    if !rem.is_zero() && rem.sign == divisor.sign {
+        (quo + 1u8, rem - divisor)
     } else {
-        (BigInt::from_vec(quo), None)
+        (quo, rem)
     }
 }
 
-/// Returns (dividend / divisor, remainder). This algorithm taken from TAOCP 4.3.1
-pub(crate) fn divmod(
-    mut dividend: BigInt,
-    mut divisor: BigInt,
+/// Returns the quotient and remainder of Euclidean division, where the remainder is never
+/// negative.
+pub(crate) fn euclid_divmod<L: Limb>(
+    dividend: BigInt<L>,
+    divisor: BigInt<L>,
+) -> (BigInt<L>, BigInt<L>) {
+    let (quo, rem) = truncated_divmod(dividend, divisor.clone(), true);
+    let rem = rem.unwrap();
+    if !rem.is_negative() {
+        (quo, rem)
+    } else if divisor.is_positive() {
+        (quo - 1u8, rem + divisor)
+    } else {
+        (quo + 1u8, rem - divisor)
+    }
+}
+
+/// Returns the quotient rounded to an integer according to `mode`.
+pub(crate) fn round_div<L: Limb>(
+    dividend: BigInt<L>,
+    divisor: BigInt<L>,
+    mode: RoundingMode,
+) -> BigInt<L> {
+    use self::RoundingMode::*;
+
+    let direction = dividend.sign * divisor.sign;
+    let (quo, rem) = truncated_divmod(dividend, divisor.clone(), true);
+    let rem = rem.unwrap();
+    if rem.is_zero() {
+        return quo;
+    }
+
+    // Compare the discarded fraction |rem / divisor| against one half.
+    let half_cmp = (rem.abs() << 1usize).cmp(&divisor.abs());
+    let away = match (mode, half_cmp) {
+        (TowardZero, _) => false,
+        (AwayFromZero, _) => true,
+        (_, Less) => false,
+        (_, Greater) => true,
+        (HalfUp, Equal) => true,
+        (HalfDown, Equal) => false,
+        (HalfEven, Equal) => !quo.is_zero() && quo.digits[0] & L::ONE == L::ONE,
+    };
+
+    if !away {
+        quo
+    } else if direction == Sign::Positive {
+        quo + 1u8
+    } else {
+        quo - 1u8
+    }
+}
+
+/// Returns (dividend / divisor, remainder) for nonnegative arguments. This algorithm taken from
+/// TAOCP 4.3.1
+pub(crate) fn divmod<L: Limb>(
+    mut dividend: BigInt<L>,
+    mut divisor: BigInt<L>,
     return_remainder: bool,
-) -> (BigInt, Option<BigInt>) {
+) -> (BigInt<L>, Option<BigInt<L>>) {
     assert!(!divisor.is_zero(), "Can't divide by zero");
     if dividend.is_zero() {
         if return_remainder {
-            return (BigInt::zero(), Some(BigInt::zero()));
+            return (BigInt::default(), Some(BigInt::default()));
         } else {
-            return (BigInt::zero(), None);
+            return (BigInt::default(), None);
         }
     }
     let cmp = dividend.cmp(&divisor);
     match cmp {
-        Equal => return (BigInt::one(), Some(BigInt::zero())),
-        Less => return (BigInt::zero(), Some(dividend.clone())),
+        Equal => return (BigInt::from_primitive(1u8), Some(BigInt::default())),
+        Less => return (BigInt::default(), Some(dividend)),
         Greater => (),
     }
 
+    if divisor.digits.len() == 1 {
+        let (quo, rem) = short_divmod(&dividend, divisor.digits[0], return_remainder);
+        return (quo, rem.map(|r| BigInt::from_sign_limbs(Sign::Positive, vec![r])));
+    }
+
     let shift_size = normalization_shift_size(&divisor) as usize;
 
     // TODO: https://github.com/rust-lang/rust/issues/25753
     dividend.shl_assign(shift_size);
     divisor.shl_assign(shift_size);
+    // The loop below always looks at one digit above the current window of the dividend.
+    dividend.digits.push(L::ZERO);
 
-    let mut quotient: Vec<BigDigit>;
+    let mut quotient: LimbVec<L>;
     {
         // Constants for the division loop.
         let m = dividend.digits.len() - divisor.digits.len() - 1;
         let n = divisor.digits.len();
         let u = &mut dividend.digits;
-        let v = &mut divisor.digits;
-        let b = DIGIT_MAX as DoubleBigDigit + 1;
-
-        quotient = vec![0; m + 1];
-
-        let mut j = m;
-
-        while j != usize::max_value() {
-            let trial = (u[j + n] as DoubleBigDigit * b) + u[j + n - 1] as DoubleBigDigit;
-            let mut qhat = trial / (v[n - 1] as DoubleBigDigit) + 1;
-            let mut rhat = trial % (v[n - 1] as DoubleBigDigit);
-
-            loop {
-                if (qhat == b) ||
-                    (qhat * v[n - 2] as DoubleBigDigit >
-                        (rhat * b) + u[j + n - 2] as DoubleBigDigit)
-                {
-                    qhat -= 1;
-                    rhat += v[n - 1] as DoubleBigDigit;
-                    if rhat < b {
-                        continue;
-                    }
+        let v = &divisor.digits;
+        let b = L::MAX.widen() + L::ONE.widen();
+        let one = L::ONE.widen();
+
+        quotient = LimbVec::zeroed(m + 1);
+
+        for j in (0..m + 1).rev() {
+            let trial = L::from_lo_hi([u[j + n - 1], u[j + n]]);
+            let mut qhat = trial / v[n - 1].widen();
+            let mut rhat = trial % v[n - 1].widen();
+
+            while qhat >= b || qhat * v[n - 2].widen() > (rhat << L::BITS) + u[j + n - 2].widen() {
+                qhat = qhat - one;
+                rhat = rhat + v[n - 1].widen();
+                if rhat >= b {
+                    break;
                 }
-                break;
             }
 
-            quotient[j] = qhat as BigDigit;
-            let borrow = ssub_with_mul(&mut u[j..j + n + 1], v, qhat as BigDigit);
-
+            let borrow = ssub_with_mul(&mut u[j..j + n + 1], v, L::to_lo_hi(qhat)[0]);
 
             if borrow {
-                dsub(&mut quotient[j..], 1);
-                let carry = sadd(&mut u[j..j + n], &v);
-                debug_assert_eq!(carry, 1);
+                qhat = qhat - one;
+                // The carry out of the top digit cancels the borrow from the subtraction.
+                sadd(&mut u[j..j + n + 1], v);
             }
-            j = j.wrapping_sub(1);
+            quotient[j] = L::to_lo_hi(qhat)[0];
         }
     }
 
-    let quo = BigInt {
-        sign: Sign::Positive,
-        digits: quotient,
-    };
+    let quo = BigInt::from_vec(quotient);
     if return_remainder {
         dividend.digits.truncate(divisor.digits.len());
         (quo, Some(dividend.trimmed() >> shift_size))
@@ -137,52 +318,127 @@ pub(crate) fn divmod(
     }
 }
 
-/// Sets dividend to dividend - q * divisor. If dividend is negative, it is left as the b's
-/// complement, where b is the radix of BigDigit.
-fn ssub_with_mul(dividend: &mut [BigDigit], divisor: &[BigDigit], q: BigDigit) -> bool {
+/// Sets dividend to dividend - q * divisor and returns whether the subtraction borrowed. If it
+/// did, dividend is left as the b's complement, where b is the radix of the limbs.
+fn ssub_with_mul<L: Limb>(dividend: &mut [L], divisor: &[L], q: L) -> bool {
+    debug_assert!(dividend.len() > divisor.len());
 
-    debug_assert!(dividend.len() >= divisor.len());
+    let mut carry = L::ZERO;
+    let (lo_part, hi_part) = dividend.split_at_mut(divisor.len());
 
-    let mut carry: BigDigit = 0;
-
-    for (l, r) in dividend.iter_mut().zip(divisor.iter().cloned()) {
-        let [lo, mut hi] = to_lo_hi((r as DoubleBigDigit) * (q as DoubleBigDigit) + (carry as DoubleBigDigit));
+    for (l, r) in lo_part.iter_mut().zip(divisor.iter().cloned()) {
+        let [lo, mut hi] = L::to_lo_hi(r.widen() * q.widen() + carry.widen());
         let (res, j) = l.overflowing_sub(lo);
-        if j { hi += 1 }
+        if j { hi = hi + L::ONE }
         *l = res;
         carry = hi;
     }
 
-    carry != 0
+    dsub(hi_part, carry)
 }
 
-fn normalization_shift_size(input: &BigInt) -> u32 {
+fn normalization_shift_size<L: Limb>(input: &BigInt<L>) -> u32 {
     input.digits.last().unwrap().leading_zeros()
 }
 
 #[test]
 fn normalization_test() {
-    use bigint::digit::constants::DIGIT_MAX;
-    use bigint::digit::BigDigit;
-    use bigint::BigInt;
+    fn check<L: Limb>() {
+        let s = BigInt::<L>::from_primitive(9274u16);
+        let size = normalization_shift_size(&s) as usize;
+        let s = s << size;
 
-    let t: BigDigit = 9274;
-    let s = BigInt::from(t);
-
-    let size = normalization_shift_size(&s) as usize;
-    let s = s << size;
-
-    assert!(*s.digits.last().unwrap() > (DIGIT_MAX / 2));
+        assert!(*s.digits.last().unwrap() > L::MAX >> 1);
+    }
+    check::<u16>();
+    check::<u32>();
+    check::<u64>();
 }
 
 #[test]
 fn short_divmod_test() {
     use std::str::FromStr;
     let dividend = BigInt::from_str("159227301757406318958308608461596464563224530763743").unwrap();
+    use bigint::digit::BigDigit;
     let divisor: BigDigit = 74495;
     let quotient = BigInt::from_str("2137422669406085226636802583550526405305383324").unwrap();
     let remainder: BigDigit = 42363;
 
     let res = short_divmod(&dividend, divisor, true);
     assert_eq!(res, (quotient, Some(remainder)));
-}
\ No newline at end of file
+}
+
+#[test]
+fn truncated_div_sign_test() {
+    let seven = BigInt::from(7u8);
+    let two = BigInt::from(2u8);
+
+    assert_eq!(&seven / &two, BigInt::from(3u8));
+    assert_eq!(-seven.clone() / &two, -BigInt::from(3u8));
+    assert_eq!(&seven / -two.clone(), -BigInt::from(3u8));
+    assert_eq!(-seven / -two, BigInt::from(3u8));
+}
+
+#[test]
+fn divmod_full_top_digit_test() {
+    use std::str::FromStr;
+    let dividend = BigInt::from_str("18446744073709551621").unwrap();
+    let divisor = BigInt::from_str("18446744073709551617").unwrap();
+
+    assert_eq!(dividend.div_mod(&divisor), (BigInt::one(), BigInt::from(4u8)));
+}
+
+#[test]
+fn floor_ceil_euclid_small_test() {
+    for a in -20i64..21 {
+        for b in (-7i64..8).filter(|&b| b != 0) {
+            let (fq, fr) = floor_divmod(BigInt::from(a), BigInt::from(b));
+            let floor_q = if (a % b != 0) && ((a < 0) != (b < 0)) { a / b - 1 } else { a / b };
+            assert_eq!((fq, fr), (BigInt::from(floor_q), BigInt::from(a - floor_q * b)));
+
+            let (cq, cr) = ceil_divmod(BigInt::from(a), BigInt::from(b));
+            let ceil_q = if (a % b != 0) && ((a < 0) == (b < 0)) { a / b + 1 } else { a / b };
+            assert_eq!((cq, cr), (BigInt::from(ceil_q), BigInt::from(a - ceil_q * b)));
+
+            let (eq, er) = euclid_divmod(BigInt::from(a), BigInt::from(b));
+            assert_eq!((eq, er), (BigInt::from(a.div_euclid(b)), BigInt::from(a.rem_euclid(b))));
+        }
+    }
+}
+
+#[test]
+fn round_div_test() {
+    use self::RoundingMode::*;
+    let cases = [
+        // (dividend, divisor, HalfEven, HalfUp, HalfDown, TowardZero, AwayFromZero)
+        (5, 2, 2, 3, 2, 2, 3),
+        (7, 2, 4, 4, 3, 3, 4),
+        (-5, 2, -2, -3, -2, -2, -3),
+        (-7, -2, 4, 4, 3, 3, 4),
+        (7, 3, 2, 2, 2, 2, 3),
+        (8, -3, -3, -3, -3, -2, -3),
+        (9, 3, 3, 3, 3, 3, 3),
+        (1, 2, 0, 1, 0, 0, 1),
+    ];
+
+    for &(a, b, even, up, down, zero, away) in cases.iter() {
+        assert_eq!(round_div(BigInt::from(a), BigInt::from(b), HalfEven), BigInt::from(even));
+        assert_eq!(round_div(BigInt::from(a), BigInt::from(b), HalfUp), BigInt::from(up));
+        assert_eq!(round_div(BigInt::from(a), BigInt::from(b), HalfDown), BigInt::from(down));
+        assert_eq!(round_div(BigInt::from(a), BigInt::from(b), TowardZero), BigInt::from(zero));
+        assert_eq!(round_div(BigInt::from(a), BigInt::from(b), AwayFromZero), BigInt::from(away));
+    }
+}
+
+#[test]
+fn div_assign_test() {
+    let mut x = -(BigInt::one() << 200usize);
+    x /= BigInt::from(4u8);
+    assert_eq!(x, -(BigInt::one() << 198usize));
+    x /= &-(BigInt::one() << 100usize);
+    assert_eq!(x, BigInt::one() << 98);
+    x /= 1u32 << 8;
+    assert_eq!(x, BigInt::one() << 90);
+    x /= &(BigInt::one() << 91);
+    assert!(x.is_zero());
+}
@@ -3,4 +3,6 @@ pub mod sub;
 pub mod mul;
 pub mod div;
 pub mod shift;
-pub mod rem;
\ No newline at end of file
+pub mod rem;
+pub mod scalar;
+pub mod bit;
@@ -1,48 +1,114 @@
-use bigint::{BigDigit, BigInt, DoubleBigDigit};
+use bigint::BigInt;
 use bigint::Sign::*;
+use bigint::sign::Sign;
 
-use bigint::digit::to_lo_hi;
-use bigint::ops::add::sadd;
+use bigint::digit::Limb;
+use bigint::limbs::LimbVec;
+use bigint::ops::add::{sadd, sadd_digit};
 use bigint::ops::sub::{ssub_sign, ssub};
 
-use std::ops::Mul;
-use std::iter::repeat;
+use std::ops::{Mul, MulAssign};
+use std::iter::{repeat, Product};
 
-impl Mul<BigInt> for BigInt {
-    type Output = BigInt;
-    fn mul(self, rhs: BigInt) -> Self::Output { naive_mul(&self, &rhs) }
+impl<L: Limb> Mul<BigInt<L>> for BigInt<L> {
+    type Output = BigInt<L>;
+    fn mul(self, rhs: BigInt<L>) -> Self::Output { naive_mul(&self, &rhs) }
 }
 
-impl<'a, 'b> Mul<&'a BigInt> for &'b BigInt {
-    type Output = BigInt;
-    fn mul(self, rhs: &'a BigInt) -> Self::Output { naive_mul(self, rhs) }
+impl<'a, 'b, L: Limb> Mul<&'a BigInt<L>> for &'b BigInt<L> {
+    type Output = BigInt<L>;
+    fn mul(self, rhs: &'a BigInt<L>) -> Self::Output { naive_mul(self, rhs) }
 }
 
-impl Mul<BigDigit> for BigInt {
-    type Output = BigInt;
-    fn mul(mut self, rhs: BigDigit) -> Self::Output {
-        if self.is_zero() || rhs == 0 {
-            return BigInt::zero();
-        }
+impl<'a, L: Limb> Mul<&'a BigInt<L>> for BigInt<L> {
+    type Output = BigInt<L>;
+    fn mul(mut self, rhs: &'a BigInt<L>) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+impl<'a, L: Limb> Mul<BigInt<L>> for &'a BigInt<L> {
+    type Output = BigInt<L>;
+    fn mul(self, mut rhs: BigInt<L>) -> Self::Output {
+        rhs *= self;
+        rhs
+    }
+}
 
-        let carry = dmul(&mut self.digits, rhs);
+impl<L: Limb> MulAssign<BigInt<L>> for BigInt<L> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: BigInt<L>) {
+        *self *= &rhs;
+    }
+}
 
-        if carry != 0 {
-            self.digits.push(carry);
+impl<'a, L: Limb> MulAssign<&'a BigInt<L>> for BigInt<L> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: &'a BigInt<L>) {
+        mul_assign_slice(self, rhs.sign, &rhs.digits);
+    }
+}
+
+impl<L: Limb> Product<BigInt<L>> for BigInt<L> {
+    fn product<I: Iterator<Item = BigInt<L>>>(iter: I) -> Self { product_tree(iter.collect()) }
+}
+
+impl<'a, L: Limb> Product<&'a BigInt<L>> for BigInt<L> {
+    fn product<I: Iterator<Item = &'a BigInt<L>>>(iter: I) -> Self {
+        product_tree(iter.cloned().collect())
+    }
+}
+
+/// Multiplies all of `factors` together by repeatedly multiplying neighbouring pairs. The
+/// operands of each product stay about the same size, so large products go through Karatsuba
+/// rather than a long chain of small times huge multiplications.
+pub(crate) fn product_tree<L: Limb>(mut factors: Vec<BigInt<L>>) -> BigInt<L> {
+    while factors.len() > 1 {
+        let mut next = Vec::with_capacity(factors.len().div_ceil(2));
+        let mut iter = factors.into_iter();
+        while let Some(a) = iter.next() {
+            next.push(match iter.next() {
+                Some(b) => a * b,
+                None => a,
+            });
         }
+        factors = next;
+    }
+    factors.pop().unwrap_or_else(|| BigInt::from_primitive(1u8))
+}
 
-        self
+/// Multiplies `lhs` by the number with sign `rhs_sign` and magnitude `rhs`. Single digit
+/// multipliers reuse the digit buffer of `lhs`.
+pub(crate) fn mul_assign_slice<L: Limb>(lhs: &mut BigInt<L>, rhs_sign: Sign, rhs: &[L]) {
+    let sign = lhs.sign * rhs_sign;
+    if sign == Zero {
+        *lhs = BigInt::default();
+        return;
     }
+
+    if rhs.len() == 1 {
+        let carry = dmul(&mut lhs.digits, rhs[0]);
+        if carry != L::ZERO {
+            lhs.digits.push(carry);
+        }
+    } else {
+        let mut digits = LimbVec::zeroed(lhs.digits.len() + rhs.len());
+        mul3(&mut digits, &lhs.digits, rhs);
+        lhs.digits = digits;
+        lhs.trim();
+    }
+    lhs.sign = sign;
 }
 
 
-pub(crate) fn naive_mul(lhs: &BigInt, rhs: &BigInt) -> BigInt {
+pub(crate) fn naive_mul<L: Limb>(lhs: &BigInt<L>, rhs: &BigInt<L>) -> BigInt<L> {
     let sign = lhs.sign * rhs.sign;
     if sign == Zero {
-        return BigInt::zero();
+        return BigInt::default();
     }
 
-    let mut digits = vec![0; lhs.digits.len() + rhs.digits.len()];
+    let mut digits = LimbVec::zeroed(lhs.digits.len() + rhs.digits.len());
 
     mul3(&mut digits, &lhs.digits, &rhs.digits);
 
@@ -51,7 +117,7 @@ pub(crate) fn naive_mul(lhs: &BigInt, rhs: &BigInt) -> BigInt {
 }
 
 /// 3 argument multiplication: `target += b * c` using different methods depending on argument lengths.
-pub(crate) fn mul3(target: &mut [BigDigit], b: &[BigDigit], c: &[BigDigit]) {
+pub(crate) fn mul3<L: Limb>(target: &mut [L], b: &[L], c: &[L]) {
     let (x, y) = if b.len() < c.len() { (b, c) } else { (c, b) };
 
     if x.len() <= 16 {
@@ -62,7 +128,7 @@ pub(crate) fn mul3(target: &mut [BigDigit], b: &[BigDigit], c: &[BigDigit]) {
 }
 
 // Cribbed from num-bigint! https://github.com/rust-num/num-bigint/blob/master/src/algorithms.rs
-pub(crate) fn k_mul3(target: &mut [BigDigit], x: &[BigDigit], y: &[BigDigit]) {
+pub(crate) fn k_mul3<L: Limb>(target: &mut [L], x: &[L], y: &[L]) {
     debug_assert!(target.len() >= x.len() + y.len());
 
     let b = x.len() / 2;
@@ -70,7 +136,7 @@ pub(crate) fn k_mul3(target: &mut [BigDigit], x: &[BigDigit], y: &[BigDigit]) {
     let (y0, y1) = y.split_at(b);
 
     let s_len = x1.len() + y1.len() + 1;
-    let mut scratch = BigInt{sign: Positive, digits: vec![0; s_len]};
+    let mut scratch = BigInt{sign: Positive, digits: LimbVec::zeroed(s_len)};
 
     mul3(&mut scratch.digits, x1, y1);
     scratch.trim();
@@ -79,7 +145,7 @@ pub(crate) fn k_mul3(target: &mut [BigDigit], x: &[BigDigit], y: &[BigDigit]) {
     sadd(&mut target[b * 2..], &scratch.digits);
 
     scratch.digits.truncate(0);
-    scratch.digits.extend(repeat(0).take(s_len));
+    scratch.digits.extend(repeat(L::ZERO).take(s_len));
 
     mul3(&mut scratch.digits, x0, y0);
     scratch.trim();
@@ -93,7 +159,7 @@ pub(crate) fn k_mul3(target: &mut [BigDigit], x: &[BigDigit], y: &[BigDigit]) {
     match j0_sign * j1_sign {
         Positive => {
             scratch.digits.truncate(0);
-            scratch.digits.extend(repeat(0).take(s_len));
+            scratch.digits.extend(repeat(L::ZERO).take(s_len));
 
             mul3(&mut scratch.digits, &j0, &j1);
             scratch.trim();
@@ -107,37 +173,30 @@ pub(crate) fn k_mul3(target: &mut [BigDigit], x: &[BigDigit], y: &[BigDigit]) {
 }
 
 /// 3 argument naive multiplication: `target += b * c`
-pub(crate) fn n_mul3(target: &mut [BigDigit], b: &[BigDigit], c: &[BigDigit]) {
+pub(crate) fn n_mul3<L: Limb>(target: &mut [L], b: &[L], c: &[L]) {
     debug_assert!(target.len() >= b.len() + c.len());
 
-    let mut carry: BigDigit = 0;
-
     for (i, l) in b.iter().cloned().enumerate() {
-        if l == 0 {
+        if l == L::ZERO {
             continue;
         }
+        let mut carry = L::ZERO;
         for (j, r) in c.iter().cloned().enumerate() {
-            let [lo, hi] = to_lo_hi(
-                l as DoubleBigDigit * r as DoubleBigDigit + target[i + j] as DoubleBigDigit +
-                    carry as DoubleBigDigit,
-            );
+            let wide = l.widen() * r.widen() + target[i + j].widen() + carry.widen();
+            let [lo, hi] = L::to_lo_hi(wide);
             target[i + j] = lo;
-            if j + 1 != c.len() {
-                carry = hi;
-            } else {
-                carry = 0;
-                target[i + j + 1] = hi;
-            }
+            carry = hi;
         }
+        sadd_digit(&mut target[i + c.len()..], carry);
     }
 }
 
-/// Multiplies a slice by a single BigDigit, returning the carry.
-pub(crate) fn dmul(lhs: &mut [BigDigit], rhs: BigDigit) -> BigDigit {
-    let rhs = rhs as DoubleBigDigit;
-    let mut carry: BigDigit = 0;
+/// Multiplies a slice by a single limb, returning the carry.
+pub(crate) fn dmul<L: Limb>(lhs: &mut [L], rhs: L) -> L {
+    let rhs = rhs.widen();
+    let mut carry = L::ZERO;
     for d in lhs.iter_mut() {
-        let [lo, hi] = to_lo_hi((*d as DoubleBigDigit * rhs) + carry as DoubleBigDigit);
+        let [lo, hi] = L::to_lo_hi((d.widen() * rhs) + carry.widen());
         *d = lo;
         carry = hi;
     }
@@ -149,39 +208,62 @@ pub(crate) fn dmul(lhs: &mut [BigDigit], rhs: BigDigit) -> BigDigit {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bigint::digit::BigDigit;
 
-    #[cfg(all(target_pointer_width = "64", not(feature = "thicc_ints")))]
     #[test]
     fn scalar_mul_test_1() {
-        use bigint::sign::Sign;
-
         let y: u32 = 915327;
-
-        let a = BigInt {
-            sign: Sign::Positive,
-            digits: vec![3059078384, 2360247638, 2634550291, 6],
-        };
-        let b = BigInt {
-            sign: Sign::Positive,
-            digits: vec![356004624, 4070707789, 1201864523, 6053427],
-        };
+        let a = BigInt::from(0x6_9d08_0c13_8cae_8556_b655_d4f0u128);
+        let b = BigInt::from(0x5c_5e33_47a2_ff4b_f2a2_124d_1538_3310u128);
 
         assert_eq!(a * y, b);
     }
 
+    #[test]
+    fn mul_assign_test() {
+        let mut acc = BigInt::one();
+        for i in 1..30u32 {
+            acc *= BigInt::from(i);
+            acc *= &-BigInt::one();
+            acc *= 1 as BigDigit;
+        }
+        let expected = (1..30u32).fold(BigInt::one(), |acc, i| acc * (i as BigDigit));
+        assert_eq!(acc, -expected);
+
+        acc *= 0 as BigDigit;
+        assert!(acc.is_zero());
+    }
+
+    #[test]
+    fn product_test() {
+        let with_zero: Vec<BigInt> = (1..500u32).map(|i| BigInt::from(i) - 3u8).collect();
+        assert!(with_zero.iter().product::<BigInt>().is_zero());
+
+        let factors: Vec<BigInt> = (1..500u32).map(|i| -BigInt::from(i)).collect();
+        let expected = factors.iter().fold(BigInt::one(), |acc, x| acc * x);
+        assert_eq!(factors.iter().product::<BigInt>(), expected);
+        assert_eq!(factors.into_iter().product::<BigInt>(), expected);
+        assert_eq!(Vec::<BigInt>::new().into_iter().product::<BigInt>(), BigInt::one());
+    }
+
     #[test]
     fn karatsuba_mul_coherence_test() {
-        use rand::{thread_rng, Rng};
-        let mut rng = thread_rng();
+        use rand::{thread_rng, Rand, Rng};
 
-        let foo: Vec<BigDigit> = rng.gen_iter().take(193).collect();
-        let bar: Vec<BigDigit> = rng.gen_iter().take(101).collect();
-        let mut k_output: Vec<BigDigit> = vec![0; foo.len() + bar.len() + 1];
-        let mut n_output: Vec<BigDigit> = vec![0; foo.len() + bar.len() + 1];
+        fn check<L: Limb + Rand>() {
+            let mut rng = thread_rng();
+            let foo: Vec<L> = rng.gen_iter().take(193).collect();
+            let bar: Vec<L> = rng.gen_iter().take(101).collect();
+            let mut k_output = vec![L::ZERO; foo.len() + bar.len() + 1];
+            let mut n_output = vec![L::ZERO; foo.len() + bar.len() + 1];
 
-        n_mul3(&mut n_output[..], &bar, &foo);
-        k_mul3(&mut k_output[..], &bar, &foo);
+            n_mul3(&mut n_output[..], &bar, &foo);
+            k_mul3(&mut k_output[..], &bar, &foo);
 
-        assert_eq!(n_output, k_output);
+            assert_eq!(n_output, k_output);
+        }
+        check::<u16>();
+        check::<u32>();
+        check::<u64>();
     }
 }
\ No newline at end of file
@@ -1,20 +1,90 @@
 use bigint::BigInt;
-use bigint::digit::BigDigit;
+use bigint::digit::Limb;
+use bigint::limbs::LimbVec;
+use bigint::sign::Sign;
 
-use bigint::ops::div::short_divmod;
+use bigint::ops::div::{drem, truncated_divmod};
 
-use std::ops::{Rem};
+use std::mem;
+use std::ops::{Rem, RemAssign};
 
-impl Rem<BigDigit> for BigInt {
-    type Output = BigDigit;
-    fn rem(self, rhs: BigDigit) -> Self::Output {
-        short_divmod(&self, rhs, true).1.unwrap()
+impl<L: Limb> Rem<BigInt<L>> for BigInt<L> {
+    type Output = BigInt<L>;
+    fn rem(self, rhs: BigInt<L>) -> Self::Output { truncated_divmod(self, rhs, true).1.unwrap() }
+}
+
+impl<'a, L: Limb> Rem<&'a BigInt<L>> for BigInt<L> {
+    type Output = BigInt<L>;
+    fn rem(self, rhs: &'a BigInt<L>) -> Self::Output {
+        truncated_divmod(self, rhs.clone(), true).1.unwrap()
+    }
+}
+
+impl<'a, L: Limb> Rem<BigInt<L>> for &'a BigInt<L> {
+    type Output = BigInt<L>;
+    fn rem(self, rhs: BigInt<L>) -> Self::Output {
+        truncated_divmod(self.clone(), rhs, true).1.unwrap()
+    }
+}
+
+impl<'a, 'b, L: Limb> Rem<&'a BigInt<L>> for &'b BigInt<L> {
+    type Output = BigInt<L>;
+    fn rem(self, rhs: &'a BigInt<L>) -> Self::Output {
+        truncated_divmod(self.clone(), rhs.clone(), true).1.unwrap()
+    }
+}
+
+impl<L: Limb> RemAssign<BigInt<L>> for BigInt<L> {
+    #[inline]
+    fn rem_assign(&mut self, rhs: BigInt<L>) {
+        *self %= &rhs;
     }
 }
 
-impl<'a> Rem<BigDigit> for &'a BigInt {
-    type Output = BigDigit;
-    fn rem(self, rhs: BigDigit) -> Self::Output {
-        short_divmod(self, rhs, true).1.unwrap()
+impl<'a, L: Limb> RemAssign<&'a BigInt<L>> for BigInt<L> {
+    #[inline]
+    fn rem_assign(&mut self, rhs: &'a BigInt<L>) {
+        rem_assign_slice(self, rhs.sign, &rhs.digits);
     }
-}
\ No newline at end of file
+}
+
+/// Replaces `lhs` with its remainder after division by the number with sign `rhs_sign` and
+/// magnitude `rhs`. The remainder takes the sign of `lhs`.
+pub(crate) fn rem_assign_slice<L: Limb>(lhs: &mut BigInt<L>, rhs_sign: Sign, rhs: &[L]) {
+    assert!(rhs_sign != Sign::Zero, "Can't divide by zero");
+    if rhs.len() == 1 {
+        let rem = drem(&lhs.digits, rhs[0]);
+        lhs.digits.clear();
+        lhs.digits.push(rem);
+        lhs.trim();
+    } else {
+        let divisor = BigInt {
+            sign: Sign::Positive,
+            digits: LimbVec::from_slice(rhs),
+        };
+        let dividend = mem::take(lhs);
+        *lhs = truncated_divmod(dividend, divisor, true).1.unwrap();
+    }
+}
+
+#[test]
+fn truncated_rem_sign_test() {
+    let seven = BigInt::from(7u8);
+    let two = BigInt::from(2u8);
+
+    assert_eq!(&seven % &two, BigInt::one());
+    assert_eq!(-seven.clone() % &two, -BigInt::one());
+    assert_eq!(&seven % -two.clone(), BigInt::one());
+    assert_eq!(-seven % -two, -BigInt::one());
+}
+
+#[test]
+fn rem_assign_test() {
+    let mut x = -(BigInt::one() << 200usize) - BigInt::from(5u8);
+    x %= &(BigInt::one() << 100);
+    assert_eq!(x, -BigInt::from(5u8));
+    x %= BigInt::from(3u8);
+    assert_eq!(x, -BigInt::from(2u8));
+    x %= 2u32;
+    assert!(x.is_zero());
+}
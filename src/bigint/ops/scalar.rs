@@ -0,0 +1,318 @@
+use bigint::BigInt;
+use bigint::digit::Limb;
+use bigint::limbs::LimbVec;
+use bigint::sign::Sign;
+
+use bigint::ops::add::add_assign_slice;
+use bigint::ops::div::div_assign_slice;
+use bigint::ops::mul::mul_assign_slice;
+use bigint::ops::rem::rem_assign_slice;
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
+
+/// The number of limbs of type `L` needed to hold the magnitude of any primitive integer.
+#[inline]
+pub(crate) fn scalar_digits<L: Limb>() -> usize { 128 / L::BITS }
+
+/// A primitive integer split into a sign and little-endian digits, kept on the stack so that
+/// mixed arithmetic never needs a temporary BigInt. There is room for the narrowest limbs.
+pub struct Scalar<L: Limb> {
+    sign: Sign,
+    digits: [L; 8],
+    len: usize,
+}
+
+impl<L: Limb> Scalar<L> {
+    fn new(sign: Sign, mut magnitude: u128) -> Scalar<L> {
+        let mut digits = [L::ZERO; 8];
+        let mut len = 0;
+        while magnitude != 0 {
+            digits[len] = L::truncate(magnitude);
+            magnitude >>= L::BITS;
+            len += 1;
+        }
+
+        let sign = if len == 0 { Sign::Zero } else { sign };
+        Scalar { sign, digits, len }
+    }
+
+    #[inline]
+    fn digits(&self) -> &[L] { &self.digits[..self.len] }
+
+    pub fn magnitude(&self) -> u128 { to_u128(self.digits()) }
+
+    #[inline]
+    pub fn is_negative(&self) -> bool { self.sign == Sign::Negative }
+
+    /// Overwrites `target` with this value, reusing its digit buffer.
+    fn assign_to(&self, target: &mut BigInt<L>) {
+        target.digits.clear();
+        target.digits.extend_from_slice(self.digits());
+        target.sign = self.sign;
+    }
+
+    pub fn into_bigint(self) -> BigInt<L> {
+        BigInt {
+            sign: self.sign,
+            digits: LimbVec::from_slice(self.digits()),
+        }
+    }
+}
+
+pub(crate) fn to_u128<L: Limb>(digits: &[L]) -> u128 {
+    digits
+        .iter()
+        .rev()
+        .fold(0, |acc, &d| (acc << L::BITS) | d.to_u128())
+}
+
+/// Returns the magnitude of `input` if it fits in a u128.
+pub(crate) fn small_magnitude<L: Limb>(input: &BigInt<L>) -> Option<u128> {
+    if input.digits.len() <= scalar_digits::<L>() {
+        Some(to_u128(&input.digits))
+    } else {
+        None
+    }
+}
+
+/// A primitive integer type. This is public so that it can bound public generic methods, but
+/// `ops` is private, so it can't be named or implemented outside of this crate.
+pub trait Primitive: Copy {
+    fn to_scalar<L: Limb>(self) -> Scalar<L>;
+}
+
+macro_rules! impl_primitive_unsigned {
+    ($($t:ty),*) => { $(
+        impl Primitive for $t {
+            #[inline]
+            fn to_scalar<L: Limb>(self) -> Scalar<L> { Scalar::new(Sign::Positive, self as u128) }
+        }
+    )* }
+}
+
+macro_rules! impl_primitive_signed {
+    ($($t:ty),*) => { $(
+        impl Primitive for $t {
+            #[inline]
+            fn to_scalar<L: Limb>(self) -> Scalar<L> {
+                if self < 0 {
+                    Scalar::new(Sign::Negative, (self as i128).wrapping_neg() as u128)
+                } else {
+                    Scalar::new(Sign::Positive, self as u128)
+                }
+            }
+        }
+    )* }
+}
+
+impl_primitive_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_primitive_signed!(i8, i16, i32, i64, i128, isize);
+
+fn div_assign_scalar<L: Limb>(lhs: &mut BigInt<L>, rhs: Scalar<L>) {
+    assert!(rhs.sign != Sign::Zero, "Can't divide by zero");
+    match small_magnitude(lhs) {
+        Some(l) => Scalar::new(lhs.sign * rhs.sign, l / rhs.magnitude()).assign_to(lhs),
+        None => div_assign_slice(lhs, rhs.sign, rhs.digits()),
+    }
+}
+
+fn rem_assign_scalar<L: Limb>(lhs: &mut BigInt<L>, rhs: Scalar<L>) {
+    assert!(rhs.sign != Sign::Zero, "Can't divide by zero");
+    match small_magnitude(lhs) {
+        Some(l) => Scalar::new(lhs.sign, l % rhs.magnitude()).assign_to(lhs),
+        None => rem_assign_slice(lhs, rhs.sign, rhs.digits()),
+    }
+}
+
+/// Returns `lhs / rhs` for a primitive `lhs`. Any BigInt too large for a u128 has a larger
+/// magnitude than every primitive, so the quotient is zero.
+fn scalar_div<L: Limb>(lhs: Scalar<L>, rhs: &BigInt<L>) -> BigInt<L> {
+    assert!(!rhs.is_zero(), "Can't divide by zero");
+    match small_magnitude(rhs) {
+        Some(r) => Scalar::new(lhs.sign * rhs.sign, lhs.magnitude() / r).into_bigint(),
+        None => BigInt::default(),
+    }
+}
+
+/// Returns `lhs % rhs` for a primitive `lhs`, with the sign of `lhs`.
+fn scalar_rem<L: Limb>(lhs: Scalar<L>, rhs: &BigInt<L>) -> BigInt<L> {
+    assert!(!rhs.is_zero(), "Can't divide by zero");
+    match small_magnitude(rhs) {
+        Some(r) => Scalar::new(lhs.sign, lhs.magnitude() % r).into_bigint(),
+        None => lhs.into_bigint(),
+    }
+}
+
+macro_rules! impl_scalar_ops {
+    ($($t:ty),*) => { $(
+        impl<L: Limb> AddAssign<$t> for BigInt<L> {
+            #[inline]
+            fn add_assign(&mut self, rhs: $t) {
+                let rhs = rhs.to_scalar();
+                add_assign_slice(self, rhs.sign, rhs.digits());
+            }
+        }
+
+        impl<L: Limb> SubAssign<$t> for BigInt<L> {
+            #[inline]
+            fn sub_assign(&mut self, rhs: $t) {
+                let rhs = rhs.to_scalar();
+                add_assign_slice(self, -rhs.sign, rhs.digits());
+            }
+        }
+
+        impl<L: Limb> MulAssign<$t> for BigInt<L> {
+            #[inline]
+            fn mul_assign(&mut self, rhs: $t) {
+                let rhs = rhs.to_scalar();
+                mul_assign_slice(self, rhs.sign, rhs.digits());
+            }
+        }
+
+        impl<L: Limb> DivAssign<$t> for BigInt<L> {
+            #[inline]
+            fn div_assign(&mut self, rhs: $t) { div_assign_scalar(self, rhs.to_scalar()); }
+        }
+
+        impl<L: Limb> RemAssign<$t> for BigInt<L> {
+            #[inline]
+            fn rem_assign(&mut self, rhs: $t) { rem_assign_scalar(self, rhs.to_scalar()); }
+        }
+
+        impl_scalar_binop!($t, Add, add, add_assign);
+        impl_scalar_binop!($t, Sub, sub, sub_assign);
+        impl_scalar_binop!($t, Mul, mul, mul_assign);
+        impl_scalar_binop!($t, Div, div, div_assign);
+        impl_scalar_binop!($t, Rem, rem, rem_assign);
+
+        impl<L: Limb> Add<BigInt<L>> for $t {
+            type Output = BigInt<L>;
+            #[inline]
+            fn add(self, rhs: BigInt<L>) -> BigInt<L> { rhs + self }
+        }
+
+        impl<'a, L: Limb> Add<&'a BigInt<L>> for $t {
+            type Output = BigInt<L>;
+            #[inline]
+            fn add(self, rhs: &'a BigInt<L>) -> BigInt<L> { rhs + self }
+        }
+
+        impl<L: Limb> Sub<BigInt<L>> for $t {
+            type Output = BigInt<L>;
+            #[inline]
+            fn sub(self, rhs: BigInt<L>) -> BigInt<L> { -(rhs - self) }
+        }
+
+        impl<'a, L: Limb> Sub<&'a BigInt<L>> for $t {
+            type Output = BigInt<L>;
+            #[inline]
+            fn sub(self, rhs: &'a BigInt<L>) -> BigInt<L> { -(rhs - self) }
+        }
+
+        impl<L: Limb> Mul<BigInt<L>> for $t {
+            type Output = BigInt<L>;
+            #[inline]
+            fn mul(self, rhs: BigInt<L>) -> BigInt<L> { rhs * self }
+        }
+
+        impl<'a, L: Limb> Mul<&'a BigInt<L>> for $t {
+            type Output = BigInt<L>;
+            #[inline]
+            fn mul(self, rhs: &'a BigInt<L>) -> BigInt<L> { rhs * self }
+        }
+
+        impl<L: Limb> Div<BigInt<L>> for $t {
+            type Output = BigInt<L>;
+            #[inline]
+            fn div(self, rhs: BigInt<L>) -> BigInt<L> { scalar_div(self.to_scalar(), &rhs) }
+        }
+
+        impl<'a, L: Limb> Div<&'a BigInt<L>> for $t {
+            type Output = BigInt<L>;
+            #[inline]
+            fn div(self, rhs: &'a BigInt<L>) -> BigInt<L> { scalar_div(self.to_scalar(), rhs) }
+        }
+
+        impl<L: Limb> Rem<BigInt<L>> for $t {
+            type Output = BigInt<L>;
+            #[inline]
+            fn rem(self, rhs: BigInt<L>) -> BigInt<L> { scalar_rem(self.to_scalar(), &rhs) }
+        }
+
+        impl<'a, L: Limb> Rem<&'a BigInt<L>> for $t {
+            type Output = BigInt<L>;
+            #[inline]
+            fn rem(self, rhs: &'a BigInt<L>) -> BigInt<L> { scalar_rem(self.to_scalar(), rhs) }
+        }
+    )* }
+}
+
+/// Implements `BigInt op $t` and `&BigInt op $t` in terms of the matching assignment operator.
+macro_rules! impl_scalar_binop {
+    ($t:ty, $tr:ident, $method:ident, $assign:ident) => {
+        impl<L: Limb> $tr<$t> for BigInt<L> {
+            type Output = BigInt<L>;
+            #[inline]
+            fn $method(mut self, rhs: $t) -> BigInt<L> {
+                self.$assign(rhs);
+                self
+            }
+        }
+
+        impl<'a, L: Limb> $tr<$t> for &'a BigInt<L> {
+            type Output = BigInt<L>;
+            #[inline]
+            fn $method(self, rhs: $t) -> BigInt<L> {
+                let mut out = self.clone();
+                out.$assign(rhs);
+                out
+            }
+        }
+    }
+}
+
+impl_scalar_ops!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+
+#[test]
+fn scalar_sign_test() {
+    let x = -BigInt::from(7u8);
+
+    assert_eq!(&x + 10i64, BigInt::from(3u8));
+    assert_eq!(&x - -7i8, BigInt::zero());
+    assert_eq!(&x * -3i32, BigInt::from(21u8));
+    assert_eq!(&x / 2u64, -BigInt::from(3u8));
+    assert_eq!(&x / -2i64, BigInt::from(3u8));
+    assert_eq!(&x % 2u128, -BigInt::one());
+    assert_eq!(&x % -2i64, -BigInt::one());
+    assert_eq!(10u8 - &x, BigInt::from(17u8));
+    assert_eq!(-15i16 / &x, BigInt::from(2u8));
+    assert_eq!(-15isize % x, -BigInt::one());
+}
+
+#[test]
+fn scalar_multi_digit_test() {
+    use std::str::FromStr;
+    let big = BigInt::from_str("340282366920938463463374607431768211455").unwrap();
+    let huge = &big * &big;
+
+    assert_eq!(&big + 1u8, BigInt::one() << 128);
+    assert_eq!(&huge / ::std::u128::MAX, big);
+    assert_eq!(&huge % ::std::u128::MAX, BigInt::zero());
+    assert_eq!((&huge + 5u8) % ::std::i128::MIN, BigInt::from(6u8));
+    assert_eq!(::std::u128::MAX * &big, huge);
+    assert_eq!(::std::u64::MAX / &huge, BigInt::zero());
+    assert_eq!(::std::i64::MIN % &huge, -(BigInt::one() << 63usize));
+}
+
+#[test]
+#[should_panic(expected = "Can't divide by zero")]
+fn scalar_div_zero_test() {
+    let _ = BigInt::from(7u8) / 0u8;
+}
+
+#[test]
+#[should_panic(expected = "Can't divide by zero")]
+fn scalar_rem_zero_test() {
+    let _ = BigInt::from(7u8) % 0i32;
+}
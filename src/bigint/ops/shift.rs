@@ -1,38 +1,89 @@
 use bigint::BigInt;
-use bigint::digit::{from_lo_hi, to_lo_hi, DoubleBigDigit};
-use bigint::digit::constants::*;
+use bigint::digit::Limb;
+use bigint::ops::add::sadd_digit;
+use bigint::ops::scalar::Primitive;
+use bigint::sign::Sign;
 
 use std::ops::{Shl, ShlAssign, Shr, ShrAssign};
-use std::iter;
 
-impl ShlAssign<usize> for BigInt {
+/// The most digits `checked_shl` will produce. Anything larger needs tens of gigabytes for a
+/// single number, which almost certainly means the shift amount is a bug.
+pub(crate) const MAX_DIGITS: usize = ::std::u32::MAX as usize;
+
+impl<L: Limb> BigInt<L> {
+    /// Returns `self << rhs`, or `None` if `rhs` is negative or the result would have an
+    /// unreasonable number of digits. Zero shifted by any nonnegative amount is zero.
+    pub fn checked_shl<T: Primitive>(&self, rhs: T) -> Option<Self> {
+        let rhs = rhs.to_scalar::<u64>();
+        if rhs.is_negative() {
+            return None;
+        }
+        if self.is_zero() {
+            return Some(BigInt::default());
+        }
+        let magnitude = rhs.magnitude();
+        if magnitude <= ::std::usize::MAX as u128 && shl_fits(self, magnitude as usize) {
+            Some(self << magnitude as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `self >> rhs`, or `None` if `rhs` is negative. Right shifts never grow a number,
+    /// so any nonnegative amount succeeds.
+    pub fn checked_shr<T: Primitive>(&self, rhs: T) -> Option<Self> {
+        let rhs = rhs.to_scalar::<u64>();
+        if rhs.is_negative() {
+            None
+        } else {
+            Some(self >> rhs.magnitude())
+        }
+    }
+}
+
+/// Returns true if `input << rhs` needs no more than `MAX_DIGITS` digits.
+fn shl_fits<L: Limb>(input: &BigInt<L>, rhs: usize) -> bool {
+    if input.is_zero() {
+        return true;
+    }
+    match input.bits().checked_add(rhs) {
+        Some(bits) => bits / L::BITS < MAX_DIGITS,
+        None => false,
+    }
+}
+
+impl<L: Limb> ShlAssign<usize> for BigInt<L> {
     fn shl_assign(&mut self, rhs: usize) {
-        if rhs == 0 {
+        if rhs == 0 || self.is_zero() {
             return;
         }
-        let (digit_shift, bit_shift) = (rhs / DIGIT_SIZE, rhs % DIGIT_SIZE);
-        // TODO: Update with .insert_slice() when that exists.
-        let extend_size = digit_shift + 1;
-        self.digits.extend(iter::repeat(0).take(extend_size));
-
-        // This is safe because we never reach past our own vector length,
-        // thanks to the above extend call.
-        unsafe {
-            let ds = self.digits.as_mut_ptr();
-            for i in (0..self.digits.len()).rev().skip(extend_size) {
-                let v = ds.offset(i as isize);
-                let [lo, hi] = to_lo_hi((*v as DoubleBigDigit) << bit_shift);
-                *v = 0;
-                *ds.offset((i + digit_shift + 1) as isize) |= hi;
-                *ds.offset((i + digit_shift) as isize) = lo;
+        assert!(shl_fits(self, rhs), "Shift amount too large");
+        let (digit_shift, bit_shift) = (rhs / L::BITS, rhs % L::BITS);
+
+        if bit_shift != 0 {
+            let mut carry = L::ZERO;
+            for d in self.digits.iter_mut() {
+                let next_carry = *d >> (L::BITS - bit_shift);
+                *d = (*d << bit_shift) | carry;
+                carry = next_carry;
+            }
+            if carry != L::ZERO {
+                self.digits.push(carry);
             }
         }
 
-        self.trim();
+        if digit_shift != 0 {
+            let len = self.digits.len();
+            self.digits.resize(len + digit_shift, L::ZERO);
+            self.digits.copy_within(..len, digit_shift);
+            for d in self.digits[..digit_shift].iter_mut() {
+                *d = L::ZERO;
+            }
+        }
     }
 }
 
-impl Shl<usize> for BigInt {
+impl<L: Limb> Shl<usize> for BigInt<L> {
     type Output = Self;
     fn shl(mut self, rhs: usize) -> Self::Output {
         self <<= rhs;
@@ -40,8 +91,8 @@ impl Shl<usize> for BigInt {
     }
 }
 
-impl<'a> Shl<usize> for &'a BigInt {
-    type Output = BigInt;
+impl<'a, L: Limb> Shl<usize> for &'a BigInt<L> {
+    type Output = BigInt<L>;
     fn shl(self, rhs: usize) -> Self::Output {
         let mut z = self.clone();
         z <<= rhs;
@@ -49,29 +100,46 @@ impl<'a> Shl<usize> for &'a BigInt {
     }
 }
 
-impl ShrAssign<usize> for BigInt {
+/// Shifting right rounds toward negative infinity, like the primitive signed integers, so that
+/// `x >> n` is the floor of `x / 2^n`.
+impl<L: Limb> ShrAssign<usize> for BigInt<L> {
     fn shr_assign(&mut self, rhs: usize) {
-        if rhs == 0 {
+        if rhs == 0 || self.is_zero() {
             return;
         }
-        let (digit_shift, bit_shift) = (rhs / DIGIT_SIZE, rhs % DIGIT_SIZE);
-
-        unsafe {
-            let ds = self.digits.as_mut_ptr();
-            for i in (0..self.digits.len()).skip(digit_shift) {
-                let v = ds.offset(i as isize);
-                let shifted = from_lo_hi([0, *v]) >> bit_shift;
-                let [lo, hi] = to_lo_hi(shifted);
-                *v = 0;
-                *ds.offset((i.saturating_sub(digit_shift).saturating_sub(1)) as isize) |= lo;
-                *ds.offset((i.saturating_sub(digit_shift)) as isize) = hi;
+        // A negative number rounds away from zero whenever a one bit is shifted out.
+        let round_down = self.is_negative() && self.trailing_zeros().unwrap() < rhs;
+        let (digit_shift, bit_shift) = (rhs / L::BITS, rhs % L::BITS);
+
+        if digit_shift >= self.digits.len() {
+            self.digits.clear();
+        } else {
+            let len = self.digits.len();
+            self.digits.copy_within(digit_shift.., 0);
+            self.digits.truncate(len - digit_shift);
+
+            if bit_shift != 0 {
+                let mut carry = L::ZERO;
+                for d in self.digits.iter_mut().rev() {
+                    let next_carry = *d << (L::BITS - bit_shift);
+                    *d = (*d >> bit_shift) | carry;
+                    carry = next_carry;
+                }
+            }
+        }
+
+        if round_down {
+            let carry = sadd_digit(&mut self.digits, L::ONE);
+            if carry != L::ZERO {
+                self.digits.push(carry);
             }
-            self.trim();
+            self.sign = Sign::Negative;
         }
+        self.trim();
     }
 }
 
-impl Shr<usize> for BigInt {
+impl<L: Limb> Shr<usize> for BigInt<L> {
     type Output = Self;
     fn shr(mut self, rhs: usize) -> Self::Output {
         self >>= rhs;
@@ -79,8 +147,8 @@ impl Shr<usize> for BigInt {
     }
 }
 
-impl<'a> Shr<usize> for &'a BigInt {
-    type Output = BigInt;
+impl<'a, L: Limb> Shr<usize> for &'a BigInt<L> {
+    type Output = BigInt<L>;
     fn shr(self, rhs: usize) -> Self::Output {
         let mut z = self.clone();
         z >>= rhs;
@@ -88,6 +156,60 @@ impl<'a> Shr<usize> for &'a BigInt {
     }
 }
 
+/// Converts a primitive shift amount to a usize. Amounts too large for a usize can still be
+/// used for right shifts, which saturate at zero or negative one.
+fn shift_amount<T: Primitive>(rhs: T, saturate: bool) -> usize {
+    let rhs = rhs.to_scalar::<u64>();
+    assert!(!rhs.is_negative(), "Negative shift amount");
+    let magnitude = rhs.magnitude();
+    if magnitude > ::std::usize::MAX as u128 {
+        assert!(saturate, "Shift amount too large");
+        ::std::usize::MAX
+    } else {
+        magnitude as usize
+    }
+}
+
+macro_rules! impl_shift_primitive {
+    ($($t:ty),*) => { $(
+        impl<L: Limb> ShlAssign<$t> for BigInt<L> {
+            #[inline]
+            fn shl_assign(&mut self, rhs: $t) { *self <<= shift_amount(rhs, false); }
+        }
+
+        impl<L: Limb> ShrAssign<$t> for BigInt<L> {
+            #[inline]
+            fn shr_assign(&mut self, rhs: $t) { *self >>= shift_amount(rhs, true); }
+        }
+
+        impl<L: Limb> Shl<$t> for BigInt<L> {
+            type Output = BigInt<L>;
+            #[inline]
+            fn shl(self, rhs: $t) -> BigInt<L> { self << shift_amount(rhs, false) }
+        }
+
+        impl<'a, L: Limb> Shl<$t> for &'a BigInt<L> {
+            type Output = BigInt<L>;
+            #[inline]
+            fn shl(self, rhs: $t) -> BigInt<L> { self << shift_amount(rhs, false) }
+        }
+
+        impl<L: Limb> Shr<$t> for BigInt<L> {
+            type Output = BigInt<L>;
+            #[inline]
+            fn shr(self, rhs: $t) -> BigInt<L> { self >> shift_amount(rhs, true) }
+        }
+
+        impl<'a, L: Limb> Shr<$t> for &'a BigInt<L> {
+            type Output = BigInt<L>;
+            #[inline]
+            fn shr(self, rhs: $t) -> BigInt<L> { self >> shift_amount(rhs, true) }
+        }
+    )* }
+}
+
+impl_shift_primitive!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, isize);
+
 #[test]
 fn shl_equivalency_test() {
     use bigint::BigInt;
@@ -101,4 +223,68 @@ fn shl_equivalency_test() {
         let q: BigDigit = 2;
         assert_eq!(z * q.pow(t as u32), y << t);
     }
+
+    fn check<L: Limb>(q: &BigInt) {
+        let limbs = q.convert_limbs::<L>();
+        for t in 0..200usize {
+            assert_eq!((&limbs << t).convert_limbs(), q << t);
+            assert_eq!((&limbs >> t).convert_limbs(), q >> t);
+        }
+    }
+    check::<u16>(&q);
+    check::<u32>(&-q.clone());
+    check::<u64>(&q);
+}
+
+#[test]
+fn arithmetic_shr_test() {
+    let values = [-(1i64 << 40) - 3, -(1 << 32), -65, -5, -2, -1, 0, 1, 7, 1 << 32, 1 << 40 | 5];
+    for &x in values.iter() {
+        for n in 0..63 {
+            assert_eq!(BigInt::from(x) >> n, BigInt::from(x >> n));
+            assert_eq!(BigInt::from(x) >> (n as u8), BigInt::from(x >> n));
+        }
+        assert_eq!(BigInt::from(x) >> 1000usize, BigInt::from(x >> 63));
+        assert_eq!(BigInt::from(x) >> ::std::u128::MAX, BigInt::from(x >> 63));
+    }
+    assert_eq!(BigInt::from(-5) >> 1, BigInt::from(-3));
+}
+
+#[test]
+fn primitive_shl_test() {
+    let x = BigInt::from(-12345);
+    assert_eq!(&x << 70u8, &x << 70usize);
+    assert_eq!(&x << 70i64, x.clone() * (BigInt::one() << 70usize));
+
+    let mut y = x.clone();
+    y <<= 3i32;
+    y >>= 3u128;
+    assert_eq!(x, y);
+}
+
+#[test]
+#[should_panic(expected = "Negative shift amount")]
+fn negative_shift_test() {
+    let _ = BigInt::one() << -1i32;
+}
+
+#[test]
+fn checked_shift_test() {
+    use bigint::digit::BigDigit;
+    let x = BigInt::from(-12345);
+    assert_eq!(x.checked_shl(10), Some(BigInt::from(-12345 << 10)));
+    assert_eq!(x.checked_shl(::std::usize::MAX), None);
+    assert_eq!(x.checked_shl(MAX_DIGITS.saturating_mul(BigDigit::BITS as usize)), None);
+    assert_eq!(BigInt::zero().checked_shl(::std::usize::MAX), Some(BigInt::zero()));
+    assert_eq!(x.checked_shr(::std::usize::MAX), Some(BigInt::from(-1)));
+
+    // Every primitive works as a shift amount. Nonzero values can only be shifted by amounts
+    // that fit in a usize.
+    assert_eq!(x.checked_shl(10u8), x.checked_shl(10i128));
+    assert_eq!(x.checked_shl(-1i32), None);
+    assert_eq!(x.checked_shl(::std::u128::MAX), None);
+    assert_eq!(BigInt::zero().checked_shl(::std::u128::MAX), Some(BigInt::zero()));
+    assert_eq!(BigInt::zero().checked_shl(-1i8), None);
+    assert_eq!(x.checked_shr(-1i64), None);
+    assert_eq!(x.checked_shr(::std::u128::MAX), Some(BigInt::from(-1)));
 }
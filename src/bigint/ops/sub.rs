@@ -1,94 +1,65 @@
 use bigint::BigInt;
-use bigint::Sign::*;
-use bigint::digit::{BigDigit};
-use bigint::digit::constants::DIGIT_MAX;
+use bigint::digit::Limb;
 use bigint::ops::add;
 use bigint::sign::Sign;
 
-use std::ops::Sub;
-
-impl Sub<BigInt> for BigInt {
-    type Output = BigInt;
-
-    fn sub(mut self, rhs: BigInt) -> Self::Output {
-        match (self.sign, rhs.sign) {
-            (Positive, Positive) => {
-                self.grow_to_hold(rhs.digits.len());
-                if let Negative = ssub(&mut self.digits, &rhs.digits) {
-                    -self.trimmed()
-                } else {
-                    self.trimmed()
-                }
-            }
-            (Positive, Negative) => (self + -rhs),
-            (Zero, _) => -rhs,
-            (_, Zero) => self,
-            (Negative, Positive) => -(-self + rhs),
-            (Negative, Negative) => -(-self - -rhs),
-        }
+use std::ops::{Sub, SubAssign};
+
+impl<L: Limb> Sub<BigInt<L>> for BigInt<L> {
+    type Output = BigInt<L>;
+
+    fn sub(mut self, rhs: BigInt<L>) -> Self::Output {
+        self -= &rhs;
+        self
     }
 }
 
-impl<'a, 'b> Sub<&'a BigInt> for &'b BigInt {
-    type Output = BigInt;
-
-    fn sub(self, rhs: &'a BigInt) -> Self::Output {
-        match (self.sign, rhs.sign) {
-            (Positive, Positive) => {
-                let mut lhs = self.clone();
-                lhs.grow_to_hold(rhs.digits.len());
-                if let Negative = ssub(&mut lhs.digits, &rhs.digits) {
-                    -lhs.trimmed()
-                } else {
-                    lhs.trimmed()
-                }
-            }
-            (Positive, Negative) => (self.clone() - rhs),
-            (Zero, _) => -rhs.clone(),
-            (_, Zero) => self.clone(),
-            (Negative, Positive) => -(-self.clone() + rhs),
-            (Negative, Negative) => -(-self.clone() - -rhs.clone()),
-        }
+impl<'a, 'b, L: Limb> Sub<&'a BigInt<L>> for &'b BigInt<L> {
+    type Output = BigInt<L>;
+
+    fn sub(self, rhs: &'a BigInt<L>) -> Self::Output {
+        let mut lhs = self.clone();
+        lhs -= rhs;
+        lhs
     }
 }
 
-impl<'a> Sub<&'a BigInt> for BigInt {
-    type Output = BigInt;
-
-    fn sub(mut self, rhs: &'a BigInt) -> Self::Output {
-        match (self.sign, rhs.sign) {
-            (Positive, Positive) => {
-                self.grow_to_hold(rhs.digits.len());
-                if let Negative = ssub(&mut self.digits, &rhs.digits) {
-                    -self.trimmed()
-                } else {
-                    self.trimmed()
-                }
-            }
-            (Positive, Negative) => {
-                add::add_and_grow(&mut self, rhs);
-                self
-            }
-            (Zero, _) => -rhs.clone(),
-            (_, Zero) => self,
-            (Negative, Positive) => -(-self + rhs),
-            (Negative, Negative) => {
-                self.grow_to_hold(rhs.digits.len());
-                if let Negative = ssub(&mut self.digits, &rhs.digits) {
-                    -self.trimmed()
-                } else {
-                    self.trimmed()
-                }
-            }
-        }
+impl<'a, L: Limb> Sub<&'a BigInt<L>> for BigInt<L> {
+    type Output = BigInt<L>;
+
+    fn sub(mut self, rhs: &'a BigInt<L>) -> Self::Output {
+        self -= rhs;
+        self
     }
 }
 
+impl<'a, L: Limb> Sub<BigInt<L>> for &'a BigInt<L> {
+    type Output = BigInt<L>;
+
+    fn sub(self, mut rhs: BigInt<L>) -> Self::Output {
+        rhs -= self;
+        -rhs
+    }
+}
+
+impl<L: Limb> SubAssign<BigInt<L>> for BigInt<L> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: BigInt<L>) {
+        *self -= &rhs;
+    }
+}
+
+impl<'a, L: Limb> SubAssign<&'a BigInt<L>> for BigInt<L> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: &'a BigInt<L>) {
+        add::add_assign_slice(self, -rhs.sign, &rhs.digits);
+    }
+}
 
 // TODO: Optimize!
 /// "Slice subract", subtracts rhs from lhs in-place and returns
 /// the sign of the result.
-pub(crate) fn ssub(lhs: &mut [BigDigit], rhs: &[BigDigit]) -> Sign {
+pub(crate) fn ssub<L: Limb>(lhs: &mut [L], rhs: &[L]) -> Sign {
     assert!(lhs.len() >= rhs.len());
     let mut carry = false;
 
@@ -96,20 +67,20 @@ pub(crate) fn ssub(lhs: &mut [BigDigit], rhs: &[BigDigit]) -> Sign {
         let (l_lo, l_hi) = lhs.split_at_mut(rhs.len());
         for (l, r) in l_lo.iter_mut().zip(rhs.iter().cloned()) {
             let (res, c) = l.overflowing_sub(r);
-            let (res, d) = if carry { res.overflowing_sub(1) } else { (res, false) };
+            let (res, d) = if carry { res.overflowing_sub(L::ONE) } else { (res, false) };
             *l = res;
             carry = c || d;
         }
         if carry {
-            carry = dsub(l_hi, 1);
+            carry = dsub(l_hi, L::ONE);
         }
     }
 
     if carry {
         for l in lhs.iter_mut() {
-            *l = DIGIT_MAX - *l;
+            *l = L::MAX - *l;
         }
-        lhs[0] += 1;
+        add::sadd_digit(lhs, L::ONE);
     }
 
     if carry {
@@ -122,7 +93,7 @@ pub(crate) fn ssub(lhs: &mut [BigDigit], rhs: &[BigDigit]) -> Sign {
 
 }
 
-pub(crate) fn ssub_sign(lhs: &[BigDigit], rhs: &[BigDigit]) -> (Sign, Vec<BigDigit>) {
+pub(crate) fn ssub_sign<L: Limb>(lhs: &[L], rhs: &[L]) -> (Sign, Vec<L>) {
     debug_assert!(lhs.len() >= rhs.len());
     let mut output = lhs.to_owned();
     let sign = ssub(&mut output, rhs);
@@ -130,29 +101,49 @@ pub(crate) fn ssub_sign(lhs: &[BigDigit], rhs: &[BigDigit]) -> (Sign, Vec<BigDig
 }
 
 /// "Digit subtract", subtracts rhs from lhs in-place and returns whether or not a borrow and carry occurred.
-pub(crate) fn dsub(lhs: &mut [BigDigit], rhs: BigDigit) -> bool {
+pub(crate) fn dsub<L: Limb>(lhs: &mut [L], rhs: L) -> bool {
     let mut carry = rhs;
 
     for ele in lhs.iter_mut() {
-        if carry == 0 {
+        if carry == L::ZERO {
             return false;
         }
         let (res, c) = ele.overflowing_sub(carry);
         *ele = res;
-        carry = c as BigDigit;
+        carry = if c { L::ONE } else { L::ZERO };
     }
-    carry != 0
+    carry != L::ZERO
 }
 
-fn all_zero(s: &[BigDigit]) -> bool {
-    s.iter().all(|&x| x == 0)
+fn all_zero<L: Limb>(s: &[L]) -> bool {
+    s.iter().all(|&x| x == L::ZERO)
 }
 
 
 #[test]
 fn dsub_test() {
-    let mut foo = [0, 0, 5, 5];
-    let res = [BigDigit::max_value(), BigDigit::max_value(), 4, 5];
+    let mut foo = [0u16, 0, 5, 5];
+    assert!(!dsub(&mut foo, 1));
+    assert_eq!(foo, [u16::MAX, u16::MAX, 4, 5]);
+
+    let mut foo = [0u64, 0, 5, 5];
     assert!(!dsub(&mut foo, 1));
-    assert_eq!(foo, res);
+    assert_eq!(foo, [u64::MAX, u64::MAX, 4, 5]);
+}
+
+#[test]
+fn sub_assign_test() {
+    use bigint::digit::BigDigit;
+    let mut acc = BigInt::from(1000u32);
+    for i in 0..10u32 {
+        acc -= BigInt::from(i);
+        acc -= &-BigInt::from(i);
+        acc -= i as BigDigit;
+    }
+    assert_eq!(acc, BigInt::from(955u32));
+
+    let mut x = BigInt::from(5u8);
+    x -= 7;
+    assert_eq!(x, -BigInt::from(2u8));
+    assert_eq!(&BigInt::from(3u8) - BigInt::from(10u8), -BigInt::from(7u8));
 }
@@ -1,91 +1,155 @@
 use bigint::BigInt;
 use bigint::sign::Sign;
-use bigint::digit::chunk_size_from_radix;
-use bigint::digit::BigDigit;
+use bigint::digit::{BigDigit, Limb};
 use bigint::errors::BigIntParseError;
 
 use nom::IResult::*;
-use rchunks::RChunks;
 
-use std::str::{self, FromStr};
-
-static BASE_2_CHARACTERS: &str = "01";
-static BASE_8_CHARACTERS: &str = "01234567";
-static BASE_10_CHARACTERS: &str = "0123456789";
-static BASE_16_CHARACTERS: &str = "0123456789abcdef";
+use std::str::FromStr;
 
 
 impl FromStr for BigInt {
     type Err = BigIntParseError;
 
+    /// Parses a decimal number, or a binary, octal or hex one with a `0b`, `0o` or `0x` prefix.
+    /// See `from_str_radix` for the rest of the accepted syntax.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use super::BigIntParseError::*;
-        if s.is_empty() {
-            return Err(EmptyInput);
-        }
+        let builder = BigIntBuilder::parse(s, None)?;
+        Ok(builder.into_bigint())
+    }
+}
 
-        let builder = BigIntBuilder::parse_from_str(&s)?;
+impl BigInt {
+    /// Parses a number in the given radix, which must be in `2..=36`. The digits may be in
+    /// either case and can be separated by underscores as in Rust literals, and may follow a
+    /// `+` or `-` sign. Surrounding whitespace is ignored. There is no radix prefix.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<BigInt, BigIntParseError> {
+        if !(2..=36).contains(&radix) {
+            return Err(BigIntParseError::InvalidRadix);
+        }
+        let builder = BigIntBuilder::parse(s, Some(radix))?;
         Ok(builder.into_bigint())
     }
 }
 
+/// A validated number string, split into its parts.
 pub(crate) struct BigIntBuilder<'a> {
     sign: Sign,
     radix: u32,
+    /// The digits, which may contain underscores.
     digit_str: &'a str,
 }
 
 impl<'a> BigIntBuilder<'a> {
-    pub fn parse_from_str<'s, S: AsRef<str>>(
-        input: &'s S,
-    ) -> Result<BigIntBuilder<'s>, BigIntParseError> {
-        let input = input.as_ref();
-        match parse_bigint(input) {
-            Done(_, u) => Ok(u),
-            Incomplete(_) => Err(BigIntParseError::EmptyInput),
-            Error(_) => Err(BigIntParseError::Unknown),
+    /// Splits up `input`, reading the radix from its prefix if `radix` is `None`. Error offsets
+    /// are relative to the untrimmed input.
+    fn parse(input: &'a str, radix: Option<u32>) -> Result<BigIntBuilder<'a>, BigIntParseError> {
+        let trimmed = input.trim();
+        if trimmed.is_empty() {
+            return Err(BigIntParseError::EmptyInput);
+        }
+        let start = input.len() - input.trim_start().len();
+
+        // The sign and prefix are both optional, so neither parser can fail.
+        let (rest, sign) = match determine_sign(trimmed) {
+            Done(rest, sign) => (rest, sign),
+            _ => unreachable!("Sign parser failed on {:?}", trimmed),
+        };
+        let (digit_str, radix) = match radix {
+            Some(radix) => (rest, radix),
+            None => match determine_radix(rest) {
+                Done(digits, radix) => (digits, radix),
+                _ => unreachable!("Radix parser failed on {:?}", rest),
+            },
+        };
+        let prefixed = digit_str.len() != rest.len();
+
+        // Underscores may follow a digit or a radix prefix, but can't start a bare number.
+        let offset = start + trimmed.len() - digit_str.len();
+        let mut seen_digit = false;
+        for (i, c) in digit_str.char_indices() {
+            if c.is_digit(radix) {
+                seen_digit = true;
+            } else if c != '_' || !(seen_digit || prefixed) {
+                return Err(BigIntParseError::InvalidDigit { offset: offset + i, found: c });
+            }
+        }
+        if !seen_digit {
+            return Err(BigIntParseError::NoDigits);
         }
+        Ok(BigIntBuilder { sign, radix, digit_str })
     }
 
     pub fn into_bigint(self) -> BigInt {
-        let sign = self.sign;
         let radix = self.radix;
-
-        let radix_vec: Vec<BigDigit> = self.digit_str
-            .as_bytes()
-            .rchunks(chunk_size_from_radix(radix).unwrap())
-            .map(|c| {
-                BigDigit::from_str_radix(str::from_utf8(c).unwrap(), radix).unwrap()
-            })
+        let digits: Vec<u8> = self.digit_str
+            .chars()
+            .filter_map(|c| c.to_digit(radix))
+            .map(|d| d as u8)
             .collect();
 
-        let mut radix_vec_iter = radix_vec.into_iter().rev();
-        let first = radix_vec_iter.next().unwrap();
-        let mut output = BigInt::from(first);
-        output.sign = sign;
+        let mut output = if radix.is_power_of_two() {
+            pack_bits(&digits, radix.trailing_zeros() as usize)
+        } else {
+            let chunk_size = BigDigit::chunk_size_from_radix(radix).unwrap();
+            let chunks: Vec<BigDigit> = digits
+                .rchunks(chunk_size)
+                .map(|chunk| {
+                    chunk.iter().fold(0, |acc, &d| acc * radix as BigDigit + d as BigDigit)
+                })
+                .collect();
 
-        for r in radix_vec_iter {
-            output = 
-                (output * (radix as BigDigit).pow(chunk_size_from_radix(radix).unwrap() as u32)) + r;
-        }
+            // powers[k] is radix^(chunk_size * 2^k), enough to split the chunks in half.
+            let mut powers = vec![BigInt::from((radix as BigDigit).pow(chunk_size as u32))];
+            while 1 << powers.len() < chunks.len() {
+                let next = powers.last().unwrap() * powers.last().unwrap();
+                powers.push(next);
+            }
+            combine_chunks(&chunks, &powers)
+        };
+        output.sign = output.sign * self.sign;
         output
     }
 }
 
-named!(parse_bigint<&str, BigIntBuilder>,
-    do_parse!(
-        sign: determine_sign >>
-        radix: determine_radix >>
-        digit_str: switch!(value!(radix),
-            2 => is_a_s!(BASE_2_CHARACTERS) |
-            8 => is_a_s!(BASE_8_CHARACTERS) |
-            10 => is_a_s!(BASE_10_CHARACTERS) |
-            16 => is_a_s!(BASE_16_CHARACTERS)
-            ) >>
-        (BigIntBuilder{sign, radix, digit_str})
+/// Below this many chunks, `combine_chunks` multiplies them in one at a time.
+const COMBINE_THRESHOLD: usize = 32;
 
-));
+/// Returns the number with little-endian digits `chunks` in base `powers[0]`, where `powers[k]`
+/// is `powers[0]^(2^k)`. Large inputs are split at a power of two chunks and the halves combined
+/// with a single multiplication, so this takes O(M(n) log n) time.
+fn combine_chunks(chunks: &[BigDigit], powers: &[BigInt]) -> BigInt {
+    if chunks.len() <= COMBINE_THRESHOLD {
+        let mut out = BigInt::zero();
+        for &chunk in chunks.iter().rev() {
+            out *= &powers[0];
+            out += chunk;
+        }
+        return out;
+    }
+    // The largest power of two below the length, so the high part is never empty.
+    let k = (chunks.len() - 1).ilog2() as usize;
+    let (low, high) = chunks.split_at(1 << k);
+    combine_chunks(high, powers) * &powers[k] + combine_chunks(low, powers)
+}
 
+/// Packs `digits`, most significant first and `bits` bits each, straight into limbs.
+fn pack_bits(digits: &[u8], bits: usize) -> BigInt {
+    let limb_bits = <BigDigit as Limb>::BITS;
+    let mut limbs = Vec::with_capacity((digits.len() * bits).div_ceil(limb_bits));
+    let (mut acc, mut acc_bits) = (0u128, 0);
+    for &d in digits.iter().rev() {
+        acc |= (d as u128) << acc_bits;
+        acc_bits += bits;
+        if acc_bits >= limb_bits {
+            limbs.push(acc as BigDigit);
+            acc >>= limb_bits;
+            acc_bits -= limb_bits;
+        }
+    }
+    limbs.push(acc as BigDigit);
+    BigInt::from_sign_limbs(Sign::Positive, limbs)
+}
 
 named!(determine_sign<&str, Sign>,
     alt_complete!(
@@ -100,19 +164,19 @@ named!(determine_radix<&str, u32>,
 
 named!(hex<&str, u32>,
     do_parse!(
-        tag!("0x") >>
+        tag_no_case_s!("0x") >>
         (16)
 ));
 
 named!(octal<&str, u32>,
     do_parse!(
-        tag!("0o") >>
+        tag_no_case_s!("0o") >>
         (8)
 ));
 
 named!(binary<&str, u32>,
     do_parse!(
-        tag!("0b") >>
+        tag_no_case_s!("0b") >>
         (2)
 ));
 
@@ -120,16 +184,11 @@ named!(decimal<&str, u32>,
     value!(10)
 );
 
-#[cfg(all(target_pointer_width = "64", not(feature = "thicc_ints")))]
 #[test]
 fn binary_parse_test_1() {
     let s = "0b1000100111010001011001011111101001111000";
     let s_int = BigInt::from_str(s).unwrap();
-    let q_int = BigInt {
-        sign: Sign::Positive,
-        digits: vec![0b11010001011001011111101001111000, 0b10001001],
-    };
-    assert_eq!(s_int, q_int);
+    assert_eq!(s_int.limbs_u64(), vec![0b10001001_11010001011001011111101001111000]);
 }
 
 #[test]
@@ -138,3 +197,83 @@ fn negative_parse_test_1() {
     let s_int = BigInt::from_str(s).unwrap();
     assert!(s_int.is_negative());
 }
+
+#[test]
+fn zero_parse_test() {
+    assert!(BigInt::from_str("0").unwrap().is_zero());
+    assert!(BigInt::from_str("-0").unwrap().is_zero());
+    assert!(BigInt::from_str("0x000").unwrap().is_zero());
+}
+
+#[test]
+fn strict_parse_test() {
+    use bigint::errors::BigIntParseError::*;
+    let err = |s: &str| BigInt::from_str(s).unwrap_err();
+    assert_eq!(err(""), EmptyInput);
+    assert_eq!(err("-"), NoDigits);
+    assert_eq!(err("0x"), NoDigits);
+    assert_eq!(err("+0b"), NoDigits);
+    assert_eq!(err("123abc"), InvalidDigit { offset: 3, found: 'a' });
+    assert_eq!(err("0x12zz"), InvalidDigit { offset: 4, found: 'z' });
+    assert_eq!(err("0b102"), InvalidDigit { offset: 4, found: '2' });
+    assert_eq!(err("-\u{e9}1"), InvalidDigit { offset: 1, found: '\u{e9}' });
+    assert_eq!(err("1\u{e9}"), InvalidDigit { offset: 1, found: '\u{e9}' });
+    assert_eq!(err("+-5"), InvalidDigit { offset: 1, found: '-' });
+    assert_eq!(err("12 _"), InvalidDigit { offset: 2, found: ' ' });
+    assert_eq!(err("0x12zz").to_string(), "invalid digit 'z' at byte 4");
+    assert_eq!(BigInt::from_str("0x12").unwrap(), BigInt::from(0x12u8));
+    assert_eq!(BigInt::from_str("-0o17").unwrap(), -BigInt::from(15u8));
+}
+
+#[test]
+fn radix_parse_test() {
+    use bigint::errors::BigIntParseError::*;
+    assert_eq!(BigInt::from_str_radix("zz", 36), Ok(BigInt::from(1295u16)));
+    assert_eq!(BigInt::from_str_radix("-Zz", 36), Ok(-BigInt::from(1295u16)));
+    assert_eq!(BigInt::from_str_radix(" +1_000_000\n", 10), Ok(BigInt::from(1000000u32)));
+    assert_eq!(BigInt::from_str_radix("1__2_", 3), Ok(BigInt::from(5u8)));
+    assert_eq!(BigInt::from_str_radix("0x10", 16), Err(InvalidDigit { offset: 1, found: 'x' }));
+    assert_eq!(BigInt::from_str_radix("_1", 10), Err(InvalidDigit { offset: 0, found: '_' }));
+    assert_eq!(BigInt::from_str_radix(" 12", 2), Err(InvalidDigit { offset: 2, found: '2' }));
+    assert_eq!(BigInt::from_str_radix("1", 1), Err(InvalidRadix));
+    assert_eq!(BigInt::from_str_radix("1", 37), Err(InvalidRadix));
+    assert_eq!(BigInt::from_str_radix(" \t", 10), Err(EmptyInput));
+
+    assert_eq!(BigInt::from_str("0XfF").unwrap(), BigInt::from(255u8));
+    assert_eq!(BigInt::from_str("-0B1_01").unwrap(), -BigInt::from(5u8));
+    assert_eq!(BigInt::from_str("0O17").unwrap(), BigInt::from(15u8));
+    assert_eq!(BigInt::from_str("\t0x_dead_BEEF ").unwrap(), BigInt::from(0xdead_beefu32));
+    assert_eq!(BigInt::from_str("0x_"), Err(NoDigits));
+    assert_eq!(BigInt::from_str(" 1 2"), Err(InvalidDigit { offset: 2, found: ' ' }));
+
+    // Long inputs span many chunks, whose size depends on the radix.
+    let hex = "0xffffffffffffffffffffffffffffffff";
+    assert_eq!(BigInt::from_str(hex).unwrap(), (BigInt::one() << 128usize) - 1u8);
+    let digits = "6543210_6543210_6543210_6543210_6543210_6543210";
+    for radix in 7..37 {
+        let expected = digits
+            .chars()
+            .filter_map(|c| c.to_digit(radix))
+            .fold(BigInt::zero(), |acc, d| acc * radix + d);
+        assert_eq!(BigInt::from_str_radix(digits, radix), Ok(expected));
+    }
+}
+
+#[test]
+fn large_parse_test() {
+    use rand::{thread_rng, Rng};
+    let mut rng = thread_rng();
+    for &radix in [2, 4, 8, 10, 16, 32, 36, 3].iter() {
+        for &len in [1, 31, 500, 5000].iter() {
+            let s: String = (0..len)
+                .map(|_| ::std::char::from_digit(rng.gen_range(0, radix), radix).unwrap())
+                .collect();
+            let expected = s
+                .chars()
+                .fold(BigInt::zero(), |acc, c| acc * radix + c.to_digit(radix).unwrap());
+            assert_eq!(BigInt::from_str_radix(&s, radix), Ok(expected));
+        }
+    }
+    let s = format!("-{}", "0".repeat(3000));
+    assert!(BigInt::from_str(&s).unwrap().is_zero());
+}
@@ -0,0 +1,158 @@
+use bigint::{BigInt, Limb};
+use bigint::ops::shift::MAX_DIGITS;
+
+impl<L: Limb> BigInt<L> {
+    /// Returns self raised to the power `exp`. Panics if the result would have an unreasonable
+    /// number of digits; see `checked_pow`.
+    pub fn pow(&self, exp: u32) -> Self {
+        self.checked_pow(exp).expect("Result of pow is too large")
+    }
+
+    /// Returns self raised to the power `exp`, or `None` if the result would have more digits
+    /// than `checked_shl` allows.
+    pub fn checked_pow(&self, exp: u32) -> Option<Self> {
+        let max_bits = (MAX_DIGITS as u64) * (L::BITS as u64);
+        if self.bits() > 1 && (self.bits() as u64) * (exp as u64) > max_bits {
+            return None;
+        }
+        Some(window_pow(self, &BigInt::from_primitive(exp), |a, b| a * b))
+    }
+
+    /// Returns self raised to the power `exp`. Panics if `exp` is negative.
+    pub fn pow_big(&self, exp: &Self) -> Self {
+        assert!(!exp.is_negative(), "Negative exponent");
+        window_pow(self, exp, |a, b| a * b)
+    }
+
+    /// Returns self raised to the power `exp`, reduced modulo `modulus` into the range
+    /// `0..|modulus|`. A negative `exp` raises the modular inverse of self instead, and panics if
+    /// self has no inverse modulo `modulus`. Panics if `modulus` is zero.
+    pub fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self {
+        assert!(!modulus.is_zero(), "Modulus must be nonzero");
+        let mut modulus = modulus.clone();
+        if modulus.is_negative() {
+            modulus.negate();
+        }
+
+        let mut base = self.rem_euclid(&modulus);
+        let mut exp = exp.clone();
+        if exp.is_negative() {
+            base = base.mod_inverse(&modulus).expect("Base is not invertible modulo the modulus");
+            exp.negate();
+        }
+
+        window_pow(&base, &exp, |a, b| {
+            let mut out = a * b;
+            out %= &modulus;
+            out
+        }) % &modulus
+    }
+}
+
+/// Picks the sliding window width for an exponent with `bits` bits, balancing the size of the
+/// precomputed table against the number of multiplications it saves.
+fn window_size(bits: usize) -> usize {
+    match bits {
+        0..=7 => 1,
+        8..=36 => 3,
+        37..=140 => 4,
+        141..=450 => 5,
+        451..=1303 => 6,
+        _ => 7,
+    }
+}
+
+/// Raises `base` to the nonnegative power `exp` by left-to-right sliding window
+/// exponentiation (HAC 14.85), using `mul` for every product and square.
+fn window_pow<L, F>(base: &BigInt<L>, exp: &BigInt<L>, mul: F) -> BigInt<L>
+where
+    L: Limb,
+    F: Fn(&BigInt<L>, &BigInt<L>) -> BigInt<L>,
+{
+    let bits = exp.bits();
+    if bits == 0 {
+        let one = BigInt::from_primitive(1u8);
+        return mul(&one, &one);
+    }
+
+    // table[i] holds base^(2i + 1).
+    let k = window_size(bits);
+    let mut table = vec![mul(base, &BigInt::from_primitive(1u8))];
+    if k > 1 {
+        let square = mul(base, base);
+        for i in 1..(1 << (k - 1)) {
+            let next = mul(&table[i - 1], &square);
+            table.push(next);
+        }
+    }
+
+    let mut acc: Option<BigInt<L>> = None;
+    let mut i = bits;
+    while i > 0 {
+        if !exp.bit(i - 1) {
+            acc = acc.map(|a| mul(&a, &a));
+            i -= 1;
+            continue;
+        }
+
+        // Find the longest window exp[l..i] of at most k bits that ends in a one bit.
+        let mut l = i.saturating_sub(k);
+        while !exp.bit(l) {
+            l += 1;
+        }
+        let window = exp.extract_bits_u64(l..i) as usize;
+
+        acc = Some(match acc {
+            Some(mut a) => {
+                for _ in l..i {
+                    a = mul(&a, &a);
+                }
+                mul(&a, &table[window >> 1])
+            }
+            None => table[window >> 1].clone(),
+        });
+        i = l;
+    }
+    acc.unwrap()
+}
+
+
+#[test]
+fn pow_test() {
+    let base = -BigInt::from(12345u32);
+    let mut expected = BigInt::one();
+    for exp in 0..200 {
+        assert_eq!(base.pow(exp), expected);
+        assert_eq!(base.pow_big(&BigInt::from(exp)), expected);
+        expected *= &base;
+    }
+    assert_eq!(BigInt::zero().pow(0), BigInt::one());
+    assert_eq!(BigInt::zero().pow(5), BigInt::zero());
+}
+
+#[test]
+fn checked_pow_test() {
+    assert_eq!(BigInt::from(2u8).checked_pow(100), Some(BigInt::one() << 100usize));
+    assert_eq!((BigInt::one() << 1000usize).checked_pow(::std::u32::MAX), None);
+    assert_eq!((-BigInt::one()).checked_pow(::std::u32::MAX), Some(-BigInt::one()));
+}
+
+#[test]
+fn pow_mod_test() {
+    use std::str::FromStr;
+    // 2^127 - 1 is prime, so Fermat's little theorem applies.
+    let p = (BigInt::one() << 127usize) - BigInt::one();
+    let a = BigInt::from_str("98237498237492837492837498234").unwrap();
+    assert_eq!(a.pow_mod(&(&p - 1u8), &p), BigInt::one());
+    assert_eq!(a.pow_mod(&p, &p), a);
+
+    let m = BigInt::from(1000000007u32);
+    let e = BigInt::from(123456789u32);
+    assert_eq!(a.pow_mod(&e, &m), BigInt::from(552767632u32));
+    assert_eq!((-a.clone()).pow_mod(&e, &-m.clone()), BigInt::from(447232375u32));
+
+    assert_eq!(BigInt::from(3u8).pow_mod(&-BigInt::one(), &BigInt::from(7u8)), BigInt::from(5u8));
+    let inv = a.pow_mod(&-e.clone(), &m);
+    assert_eq!((inv * a.pow_mod(&e, &m)) % &m, BigInt::one());
+    assert!(a.pow_mod(&e, &BigInt::one()).is_zero());
+}
@@ -0,0 +1,41 @@
+/// Returns the primes up to and including `n`, by the sieve of Eratosthenes.
+pub(crate) fn primes_up_to(n: u32) -> Vec<u32> {
+    let n = n as usize;
+    let mut composite = vec![false; n.checked_add(1).expect("Sieve is too large")];
+    let mut primes = Vec::new();
+    for i in 2..=n {
+        if composite[i] {
+            continue;
+        }
+        primes.push(i as u32);
+        // i * i overflows a 32 bit usize once i passes 65535, and then there is nothing to mark.
+        if let Some(start) = i.checked_mul(i) {
+            for j in (start..=n).step_by(i) {
+                composite[j] = true;
+            }
+        }
+    }
+    primes
+}
+
+
+#[test]
+fn primes_test() {
+    assert_eq!(primes_up_to(1), vec![]);
+    assert_eq!(primes_up_to(2), vec![2]);
+    assert_eq!(primes_up_to(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+
+    // On 32 bit targets the squares of these primes overflow a usize.
+    let trial_division = (65_000..66_000)
+        .filter(|&p: &u32| (2..).take_while(|d| d * d <= p).all(|d| p % d != 0))
+        .collect::<Vec<_>>();
+    let sieved = primes_up_to(66_000).into_iter().filter(|&p| p >= 65_000).collect::<Vec<_>>();
+    assert_eq!(sieved, trial_division);
+}
+
+#[test]
+#[cfg(target_pointer_width = "32")]
+#[should_panic(expected = "Sieve is too large")]
+fn primes_overflow_test() {
+    let _ = primes_up_to(::std::u32::MAX);
+}
@@ -0,0 +1,177 @@
+use bigint::{BigInt, Limb};
+use bigint::primes::primes_up_to;
+
+impl<L: Limb> BigInt<L> {
+    /// Returns the square root of self rounded down. Panics if self is negative.
+    pub fn sqrt(&self) -> Self {
+        assert!(!self.is_negative(), "Square root of a negative number");
+        root_floor(self, 2)
+    }
+
+    /// Returns `(s, r)` where `s` is `self.sqrt()` and `r = self - s * s`. Panics if self is
+    /// negative.
+    pub fn sqrt_rem(&self) -> (Self, Self) {
+        let s = self.sqrt();
+        let r = self - &s * &s;
+        (s, r)
+    }
+
+    /// Returns the `n`th root of self rounded toward zero. Odd roots of negative numbers are
+    /// negative. Panics if `n` is zero, or if `n` is even and self is negative.
+    pub fn nth_root(&self, n: u32) -> Self {
+        assert!(n != 0, "Zeroth root is undefined");
+        assert!(!self.is_negative() || n % 2 == 1, "Even root of a negative number");
+        let mut root = root_floor(&self.abs(), n);
+        if self.is_negative() {
+            root.negate();
+        }
+        root
+    }
+
+    /// Returns true if self is the square of an integer.
+    pub fn is_perfect_square(&self) -> bool {
+        if self.is_negative() {
+            return false;
+        }
+        // Squares are 0, 1, 4 or 9 modulo 16.
+        match self.digits.first() {
+            Some(&d) if (0x213 >> (d.to_u128() & 15)) & 1 == 0 => false,
+            _ => self.sqrt_rem().1.is_zero(),
+        }
+    }
+
+    /// Returns `(base, k)` with the largest `k >= 2` such that `base.pow(k) == self`, or `None`
+    /// if there is no such `k`. Zero and one are not considered perfect powers, and a negative
+    /// number can only be an odd power of a negative base.
+    pub fn as_perfect_power(&self) -> Option<(Self, u32)> {
+        let mut base = self.abs();
+        if base.bits() <= 1 {
+            return None;
+        }
+
+        // If base = r^k then base is a p-th power for every prime p dividing k, so strip prime
+        // roots until none are left.
+        let mut k = 1u32;
+        let primes = primes_up_to(base.bits() as u32);
+        for &p in primes.iter().filter(|&&p| p != 2 || !self.is_negative()) {
+            while (p as usize) < base.bits() {
+                // The exponent of every prime factor of a p-th power is a multiple of p.
+                let excess_twos = base.trailing_zeros().unwrap() as u32 % p;
+                if excess_twos != 0 {
+                    break;
+                }
+                let root = root_floor(&base, p);
+                if root.pow(p) != base {
+                    break;
+                }
+                base = root;
+                k *= p;
+            }
+        }
+
+        if k == 1 {
+            return None;
+        }
+        if self.is_negative() {
+            base.negate();
+        }
+        Some((base, k))
+    }
+}
+
+/// Returns a positive estimate of the `n`th root of the positive number `a`, computed from its
+/// top 64 bits in floating point.
+fn root_estimate<L: Limb>(a: &BigInt<L>, n: u32) -> BigInt<L> {
+    let bits = a.bits();
+    let shift = bits.saturating_sub(64);
+    let top = a.extract_bits_u64(shift..bits) as f64;
+
+    // a ~ top * 2^shift, so root(a) ~ 2^(shift / n) * root(top * 2^(shift % n)).
+    let (whole, frac) = (shift / n as usize, shift % n as usize);
+    let scaled = 2f64.powf((top.log2() + frac as f64) / n as f64);
+    BigInt::from_primitive(scaled as u64 + 1) << whole
+}
+
+/// Returns the `n`th root of the nonnegative number `a` rounded down, by Newton iteration.
+fn root_floor<L: Limb>(a: &BigInt<L>, n: u32) -> BigInt<L> {
+    if a.is_zero() || n == 1 {
+        return a.clone();
+    }
+    // 2^(bits - 1) <= a < 2^bits <= 2^n, so the root lies in [1, 2).
+    if a.bits() <= n as usize {
+        return BigInt::from_primitive(1u8);
+    }
+
+    let step = |x: &BigInt<L>| (x * (n - 1) + a / x.pow(n - 1)) / n;
+
+    // One Newton step from any positive guess lands on or above the root, and from there the
+    // iterates decrease until they reach it.
+    let mut x = step(&root_estimate(a, n));
+    loop {
+        let y = step(&x);
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+
+#[test]
+fn sqrt_test() {
+    for x in 0u64..2000 {
+        let s = (x as f64).sqrt() as u64;
+        assert_eq!(BigInt::from(x).sqrt(), BigInt::from(s));
+        assert_eq!(BigInt::from(x).sqrt_rem().1, BigInt::from(x - s * s));
+        assert_eq!(BigInt::from(x).is_perfect_square(), s * s == x);
+    }
+
+    let big = BigInt::from(987654321987654321u64).pow(7) + BigInt::from(12345u32);
+    let (s, r) = big.sqrt_rem();
+    assert!(&s * &s <= big && (&s + 1u8) * (&s + 1u8) > big);
+    assert_eq!(r, &big - &s * &s);
+
+    let square = big.pow(2);
+    assert_eq!(square.sqrt(), big);
+    assert!(square.is_perfect_square());
+    assert!(!(square + 1u8).is_perfect_square());
+    assert!(!(-BigInt::one()).is_perfect_square());
+}
+
+#[test]
+#[should_panic]
+fn sqrt_negative_test() {
+    (-BigInt::one()).sqrt();
+}
+
+#[test]
+fn nth_root_test() {
+    let base = BigInt::from(3141592653u32);
+    assert_eq!(base.nth_root(1), base);
+    for n in 2..20 {
+        let power = base.pow(n);
+        assert_eq!(power.nth_root(n), base);
+        assert_eq!((&power - 1u8).nth_root(n), &base - 1u8);
+        assert_eq!((&power + 1u8).nth_root(n), base);
+    }
+    assert_eq!((-BigInt::from(1000u32)).nth_root(3), -BigInt::from(10u8));
+    assert_eq!((-BigInt::from(1001u32)).nth_root(3), -BigInt::from(10u8));
+    assert_eq!(BigInt::from(1000u32).nth_root(100), BigInt::one());
+    assert_eq!(BigInt::zero().nth_root(5), BigInt::zero());
+}
+
+#[test]
+fn perfect_power_test() {
+    assert_eq!(BigInt::from(64u8).as_perfect_power(), Some((BigInt::from(2u8), 6)));
+    assert_eq!(BigInt::from(1000u32).as_perfect_power(), Some((BigInt::from(10u8), 3)));
+    assert_eq!((-BigInt::from(64u8)).as_perfect_power(), Some((-BigInt::from(4u8), 3)));
+    assert_eq!((-BigInt::from(16u8)).as_perfect_power(), None);
+    assert_eq!(BigInt::from(12u8).as_perfect_power(), None);
+    assert_eq!(BigInt::one().as_perfect_power(), None);
+    assert_eq!(BigInt::zero().as_perfect_power(), None);
+
+    let base = BigInt::from(1234567u32);
+    assert_eq!(base.pow(35).as_perfect_power(), Some((base.clone(), 35)));
+    assert_eq!((base.pow(35) + 1u8).as_perfect_power(), None);
+    assert_eq!((-base.pow(15)).as_perfect_power(), Some((-base, 15)));
+}
@@ -1,10 +1,14 @@
 use std::ops::{Mul, Neg};
 use std::cmp::{PartialOrd, Ord, Ordering};
 
+/// The sign of a BigInt. Zero has its own sign rather than being positive.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Sign {
+    /// Strictly greater than zero.
     Positive,
+    /// Strictly less than zero.
     Negative,
+    /// Exactly zero.
     Zero,
 }
 
@@ -83,3 +83,108 @@ fn div_test_vector_test() {
         assert_eq!(x.div_mod(&y), (q, r));
     }
 }
+
+#[test]
+fn signed_div_test_vector_test() {
+    use numerics::bigint::BigInt;
+    use std::str::FromStr;
+
+    for line in DIV_TEST_VECTOR.lines() {
+        let mut ele = line.split_whitespace();
+        let (x, y, q, r) = (
+            ele.next().unwrap(),
+            ele.next().unwrap(),
+            ele.next().unwrap(),
+            ele.next().unwrap(),
+        );
+        let x = BigInt::from_str(x).unwrap();
+        let y = BigInt::from_str(y).unwrap();
+        let q = BigInt::from_str(q).unwrap();
+        let r = BigInt::from_str(r).unwrap();
+        assert_eq!(&x / &y, q.clone());
+        assert_eq!(&x % &y, r.clone());
+        assert_eq!(-x.clone() / &y, -q.clone());
+        assert_eq!(-x.clone() % &y, -r.clone());
+        assert_eq!(&x / -y.clone(), -q.clone());
+        assert_eq!(&x % -y.clone(), r.clone());
+        assert_eq!(-x.clone() / -y.clone(), q);
+        assert_eq!(-x % -y, -r);
+    }
+}
+
+#[test]
+fn checked_div_rem_test() {
+    use numerics::bigint::BigInt;
+
+    let x = BigInt::from(100u8);
+    assert_eq!(x.checked_div(&BigInt::zero()), None);
+    assert_eq!(x.checked_rem(&BigInt::zero()), None);
+    assert_eq!(x.checked_div(&BigInt::from(7u8)), Some(BigInt::from(14u8)));
+    assert_eq!(x.checked_rem(&BigInt::from(7u8)), Some(BigInt::from(2u8)));
+}
+
+#[test]
+fn limb_roundtrip_test() {
+    use numerics::bigint::{BigInt, Sign};
+    use std::str::FromStr;
+
+    let x = BigInt::from_str("-123456789012345678901234567890123456789").unwrap();
+    let words = x.limbs_u64();
+    let rebuilt = words.iter().rev().fold(BigInt::zero(), |acc, &w| (acc << 64usize) + w);
+    assert_eq!(-rebuilt, x);
+
+    let (sign, limbs) = x.clone().into_parts();
+    assert_eq!(sign, Sign::Negative);
+    assert_eq!(BigInt::from_sign_limbs(sign, limbs), x);
+    assert_eq!(x.abs().sign(), Sign::Positive);
+}
+
+#[test]
+fn display_test_vector_test() {
+    use numerics::bigint::BigInt;
+    use std::str::FromStr;
+
+    for line in MUL_TEST_VECTOR.lines().chain(DIV_TEST_VECTOR.lines()) {
+        for s in line.split_whitespace() {
+            assert_eq!(BigInt::from_str(s).unwrap().to_string(), s);
+        }
+    }
+}
+
+#[test]
+fn limb_width_test_vector_test() {
+    use numerics::bigint::{BigInt, Limb};
+    use std::str::FromStr;
+
+    fn parse<L: Limb>(s: &str) -> BigInt<L> { BigInt::from_str(s).unwrap().convert_limbs() }
+
+    fn check<L: Limb>() {
+        for line in MUL_TEST_VECTOR.lines() {
+            let ele: Vec<&str> = line.split_whitespace().collect();
+            let (a, b) = (parse::<L>(ele[0]), parse::<L>(ele[1]));
+            assert_eq!((&a * &b).to_string(), ele[2]);
+            assert_eq!(((&a * &b) / &b), a);
+        }
+        for line in ADD_TEST_VECTOR.lines() {
+            let ele: Vec<&str> = line.split_whitespace().collect();
+            let (a, b) = (parse::<L>(ele[0]), parse::<L>(ele[1]));
+            assert_eq!((a + b).to_string(), ele[2]);
+        }
+        for line in SUB_TEST_VECTOR.lines() {
+            let ele: Vec<&str> = line.split_whitespace().collect();
+            let (a, b) = (parse::<L>(ele[0]), parse::<L>(ele[1]));
+            assert_eq!((a - b).to_string(), ele[2]);
+        }
+        for line in DIV_TEST_VECTOR.lines() {
+            let ele: Vec<&str> = line.split_whitespace().collect();
+            let (x, y) = (parse::<L>(ele[0]), parse::<L>(ele[1]));
+            let (q, r) = x.div_mod(&y);
+            assert_eq!((q.to_string().as_str(), r.to_string().as_str()), (ele[2], ele[3]));
+            assert_eq!(q, parse::<L>(ele[2]));
+        }
+    }
+
+    check::<u16>();
+    check::<u32>();
+    check::<u64>();
+}